@@ -0,0 +1,381 @@
+use crate::percent_encoding::{is_fragment_char, percent_decode, percent_encode_bytes};
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Decoded `data:` URL, see [RFC 2397](https://www.rfc-editor.org/rfc/rfc2397)
+///
+/// - `media_type`: lowercased `type/subtype`
+/// - `parameters`: media type parameters in order, names are lowercased
+/// - `base64`: whether body is written in base64
+/// - `data`: decoded body
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct DataUrl {
+    pub media_type: String,
+    pub parameters: Vec<(String, String)>,
+    pub base64: bool,
+    pub data: Vec<u8>
+}
+
+impl DataUrl {
+    /// Creates data URL with given media type and no parameters, body is written in base64
+    pub fn new(media_type: &str, data: &[u8]) -> DataUrl {
+        DataUrl {
+            media_type: media_type.to_string(),
+            parameters: vec![],
+            base64: true,
+            data: data.to_vec()
+        }
+    }
+
+    /// Returns value of the `charset` parameter
+    pub fn charset(&self) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(name, _)| name == "charset")
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns media type with parameters, like `text/plain;charset=US-ASCII`
+    pub fn mime_type(&self) -> String {
+        let mut mime_type = self.media_type.to_string();
+        for (name, value) in self.parameters.iter() {
+            mime_type = [mime_type.as_str(), ";", name.as_str(), "=", quote_parameter_value(value).as_str()].join("");
+        }
+        mime_type
+    }
+}
+
+/// Parses `data:` URL following [WHATWG data: URL processor](https://fetch.spec.whatwg.org/#data-url-processor)
+///
+/// Media type defaults to `text/plain;charset=US-ASCII` when it is missing or invalid,
+/// body is percent-decoded and then base64-decoded if `;base64` is present, ASCII whitespace in base64 is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::parse_data_url;
+///
+/// let data_url = parse_data_url("data:text/plain;charset=utf-8;base64,SGVs bG8=").unwrap();
+/// assert_eq!("text/plain", data_url.media_type);
+/// assert_eq!("utf-8", data_url.charset().unwrap());
+/// assert!(data_url.base64);
+/// assert_eq!(b"Hello".to_vec(), data_url.data);
+///
+/// let data_url = parse_data_url("data:,A%20brief%20note").unwrap();
+/// assert_eq!("text/plain;charset=US-ASCII", data_url.mime_type());
+/// assert_eq!(b"A brief note".to_vec(), data_url.data);
+/// ```
+pub fn parse_data_url(url: &str) -> Result<DataUrl, String> {
+    let url : String = url
+        .trim_matches(|char: char| char <= ' ')
+        .chars()
+        .filter(|char| !matches!(char, '\t' | '\n' | '\r'))
+        .collect();
+    let url = url.split_once('#').map(|(url, _)| url).unwrap_or(url.as_str());
+
+    let boxed_scheme = url.split_once(':');
+    if boxed_scheme.is_none() || !boxed_scheme.unwrap().0.eq_ignore_ascii_case("data") {
+        return Err(["error: not a data URL: ", url].join(""));
+    }
+    let remaining_url = boxed_scheme.unwrap().1.trim_matches(is_ascii_whitespace);
+
+    let boxed_body = remaining_url.split_once(',');
+    if boxed_body.is_none() {
+        return Err(["error: data URL has no ',' before body: ", url].join(""));
+    }
+    let (mime_type, encoded_body) = boxed_body.unwrap();
+    let mut mime_type = mime_type.trim_matches(is_ascii_whitespace).to_string();
+    let mut data = percent_decode(encoded_body);
+
+    let mut base64 = false;
+    let without_spaces = mime_type.trim_end_matches(' ');
+    let boxed_suffix = without_spaces.len().checked_sub(6).and_then(|index| without_spaces.get(index..));
+    if boxed_suffix.is_some() && boxed_suffix.unwrap().eq_ignore_ascii_case("base64") {
+        let boxed_mime_type = without_spaces[..without_spaces.len() - 6].trim_end_matches(' ').strip_suffix(';');
+        if let Some(base64_mime_type) = boxed_mime_type {
+            base64 = true;
+            let boxed_data = forgiving_base64_decode(data.as_slice());
+            if boxed_data.is_none() {
+                return Err(["error: data URL body is not valid base64: ", url].join(""));
+            }
+            data = boxed_data.unwrap();
+            mime_type = base64_mime_type.to_string();
+        }
+    }
+
+    if mime_type.starts_with(';') {
+        mime_type = ["text/plain", mime_type.as_str()].join("");
+    }
+
+    let boxed_mime_type = parse_mime_type(mime_type.as_str());
+    let (media_type, parameters) = boxed_mime_type.unwrap_or((
+        "text/plain".to_string(),
+        vec![("charset".to_string(), "US-ASCII".to_string())]
+    ));
+
+    Ok(DataUrl { media_type, parameters, base64, data })
+}
+
+/// Builds `data:` URL, body is written in base64 or percent-encoded depending on `base64` flag
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_data_url, parse_data_url, DataUrl};
+///
+/// let data_url = DataUrl::new("image/gif", &[71, 73, 70]);
+/// assert_eq!("data:image/gif;base64,R0lG", build_data_url(&data_url));
+///
+/// let data_url = DataUrl {
+///     media_type: "text/plain".to_string(),
+///     parameters: vec![("charset".to_string(), "utf-8".to_string())],
+///     base64: false,
+///     data: "50% off, #1".as_bytes().to_vec()
+/// };
+/// let url = build_data_url(&data_url);
+/// assert_eq!("data:text/plain;charset=utf-8,50%25%20off,%20%231", url);
+/// assert_eq!(data_url, parse_data_url(url.as_str()).unwrap());
+/// ```
+pub fn build_data_url(data_url: &DataUrl) -> String {
+    let mut url = ["data:", data_url.mime_type().as_str()].join("");
+    if data_url.base64 {
+        url = [url.as_str(), ";base64,", base64_encode(data_url.data.as_slice()).as_str()].join("");
+    } else {
+        url = [url.as_str(), ",", percent_encode_bytes(data_url.data.as_slice(), is_fragment_char).as_str()].join("");
+    }
+    url
+}
+
+fn is_ascii_whitespace(char: char) -> bool {
+    matches!(char, '\t' | '\n' | '\u{0C}' | '\r' | ' ')
+}
+
+fn is_http_token_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(char)
+}
+
+fn is_http_token(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(is_http_token_char)
+}
+
+/// Simplified [MIME type parser](https://mimesniff.spec.whatwg.org/#parse-a-mime-type), `None` means failure
+fn parse_mime_type(input: &str) -> Option<(String, Vec<(String, String)>)> {
+    let input = input.trim_matches(is_ascii_whitespace);
+
+    let (media_type, mut remaining) = input.split_once('/')?;
+    if !is_http_token(media_type) {
+        return None;
+    }
+
+    let subtype_end = remaining.find(';').unwrap_or(remaining.len());
+    let subtype = remaining[..subtype_end].trim_end_matches(is_ascii_whitespace);
+    if !is_http_token(subtype) {
+        return None;
+    }
+    remaining = &remaining[subtype_end..];
+
+    let mut parameters : Vec<(String, String)> = vec![];
+    while let Some(parameter) = remaining.strip_prefix(';') {
+        let parameter = parameter.trim_start_matches(is_ascii_whitespace);
+
+        let name_end = parameter.find([';', '=']).unwrap_or(parameter.len());
+        let name = parameter[..name_end].to_ascii_lowercase();
+        remaining = &parameter[name_end..];
+
+        let boxed_value = remaining.strip_prefix('=');
+        if boxed_value.is_none() {
+            continue;
+        }
+        let after_equals = boxed_value.unwrap();
+
+        let value : String;
+        if after_equals.starts_with('"') {
+            let (quoted, rest) = collect_quoted_string(after_equals);
+            value = quoted;
+            remaining = &rest[rest.find(';').unwrap_or(rest.len())..];
+        } else {
+            let value_end = after_equals.find(';').unwrap_or(after_equals.len());
+            value = after_equals[..value_end].trim_end_matches(is_ascii_whitespace).to_string();
+            remaining = &after_equals[value_end..];
+            if value.is_empty() {
+                continue;
+            }
+        }
+
+        let is_valid_value = value.chars().all(|char| char == '\t' || (' '..='~').contains(&char) || ('\u{80}'..='\u{FF}').contains(&char));
+        let is_new_name = !parameters.iter().any(|(existing, _)| *existing == name);
+        if is_http_token(name.as_str()) && is_valid_value && is_new_name {
+            parameters.push((name, value));
+        }
+    }
+
+    Some(([media_type.to_ascii_lowercase(), "/".to_string(), subtype.to_ascii_lowercase()].join(""), parameters))
+}
+
+/// Collects value of the quoted string starting with `"`, returns the value and the rest of the input
+fn collect_quoted_string(input: &str) -> (String, &str) {
+    let mut value = String::new();
+    let mut chars = input.char_indices().skip(1);
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '"' => return (value, &input[index + 1..]),
+            '\\' => {
+                let boxed_escaped = chars.next();
+                if boxed_escaped.is_none() {
+                    value.push('\\');
+                    return (value, "");
+                }
+                value.push(boxed_escaped.unwrap().1);
+            }
+            _ => value.push(char),
+        }
+    }
+    (value, "")
+}
+
+fn quote_parameter_value(value: &str) -> String {
+    if is_http_token(value) {
+        return value.to_string();
+    }
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    ["\"", escaped.as_str(), "\""].join("")
+}
+
+/// [Forgiving-base64 decode](https://infra.spec.whatwg.org/#forgiving-base64-decode), `None` means failure
+fn forgiving_base64_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let mut data : Vec<u8> = input
+        .iter()
+        .copied()
+        .filter(|byte| !matches!(byte, b'\t' | b'\n' | 0x0C | b'\r' | b' '))
+        .collect();
+
+    if data.len() % 4 == 0 {
+        if data.ends_with(b"==") {
+            data.truncate(data.len() - 2);
+        } else if data.ends_with(b"=") {
+            data.truncate(data.len() - 1);
+        }
+    }
+    if data.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut decoded : Vec<u8> = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer : u32 = 0;
+    let mut bits = 0;
+    for byte in data {
+        let position = BASE64_ALPHABET.iter().position(|char| *char == byte)? as u32;
+        buffer = (buffer << 6) | position;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(decoded)
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    let mut encoded = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let buffer = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[(buffer >> (18 - 6 * index) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_data_url, parse_data_url, DataUrl};
+
+    #[test]
+    fn parse_data_url_whatwg_cases() {
+        let list : Vec<(&str, &str, &[u8])> = vec![
+            ("data://test/,X", "text/plain;charset=US-ASCII", b"X"),
+            ("data:,X", "text/plain;charset=US-ASCII", b"X"),
+            ("data:text/plain,X", "text/plain", b"X"),
+            ("data:;base64,WA", "text/plain;charset=US-ASCII", b"X"),
+            ("data:;base64 ,WA", "text/plain;charset=US-ASCII", b"X"),
+            ("data:x/x;base64;base64,WA", "x/x", b"X"),
+            ("data:x/x;base64;base64x,WA", "x/x", b"WA"),
+            ("data:;base64,W%20A", "text/plain;charset=US-ASCII", b"X"),
+            ("data:;base64,W%0CA", "text/plain;charset=US-ASCII", b"X"),
+            ("data:;base64,WA==", "text/plain;charset=US-ASCII", b"X"),
+            ("data:%20,%FF", "text/plain;charset=US-ASCII", &[255]),
+            ("data:;charset=utf-8,X", "text/plain;charset=utf-8", b"X"),
+            ("DATA:Text/HTML;Charset=\"utf-8\";foo,X#frag", "text/html;charset=utf-8", b"X"),
+            ("data:text/plain;a=\"b\\\"c\";a=d,X", "text/plain;a=\"b\\\"c\"", b"X"),
+            ("  data:text/plain,a\tb\nc  ", "text/plain", b"abc"),
+            ("data:text/plain,a?b=c", "text/plain", b"a?b=c"),
+            ("data:üüüa,X", "text/plain;charset=US-ASCII", b"X"),
+        ];
+
+        for (url, mime_type, data) in list {
+            let data_url = parse_data_url(url).unwrap();
+            assert_eq!(mime_type, data_url.mime_type(), "{}", url);
+            assert_eq!(data.to_vec(), data_url.data, "{}", url);
+        }
+    }
+
+    #[test]
+    fn parse_data_url_failures() {
+        assert_eq!("error: not a data URL: http://host", parse_data_url("http://host").err().unwrap());
+        assert_eq!("error: data URL has no ',' before body: data:text/plain", parse_data_url("data:text/plain").err().unwrap());
+
+        for url in ["data:;base64,WA=", "data:;base64,WAA=A", "data:;base64,W", "data:;base64,W-A"] {
+            assert!(parse_data_url(url).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn build_data_url_round_trip() {
+        let mut all_bytes = DataUrl::new("application/octet-stream", (0..=255).collect::<Vec<u8>>().as_slice());
+        for base64 in [true, false] {
+            all_bytes.base64 = base64;
+            for length in [0, 1, 2, 3, 4, 5, 256] {
+                let data_url = DataUrl { data: all_bytes.data[..length].to_vec(), ..all_bytes.clone() };
+                let url = build_data_url(&data_url);
+                assert!(!url.contains([' ', '#']), "{}", url);
+                assert_eq!(data_url, parse_data_url(url.as_str()).unwrap());
+            }
+        }
+
+        let data_url = DataUrl {
+            media_type: "text/plain".to_string(),
+            parameters: vec![("charset".to_string(), "utf-8".to_string()), ("title".to_string(), "a \"b\"".to_string())],
+            base64: false,
+            data: "Привіт".as_bytes().to_vec()
+        };
+        let url = build_data_url(&data_url);
+        assert_eq!("data:text/plain;charset=utf-8;title=\"a \\\"b\\\"\",%D0%9F%D1%80%D0%B8%D0%B2%D1%96%D1%82", url);
+        assert_eq!(data_url, parse_data_url(url.as_str()).unwrap());
+    }
+
+    #[test]
+    fn base64_encoding() {
+        let list : Vec<(&[u8], &str)> = vec![
+            (b"", ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"fooba", "Zm9vYmE="),
+            (b"foobar", "Zm9vYmFy"),
+        ];
+
+        for (data, encoded) in list {
+            let url = build_data_url(&DataUrl::new("text/plain", data));
+            assert_eq!(["data:text/plain;base64,", encoded].join(""), url);
+        }
+    }
+}
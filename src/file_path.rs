@@ -0,0 +1,391 @@
+use std::path::{Path, PathBuf};
+use crate::percent_encoding::{is_path_char, percent_decode, percent_encode_bytes};
+use crate::validation::is_valid_host;
+use crate::{UrlAuthority, UrlComponents};
+
+/// Path syntax used to convert between `file:` URLs and local paths
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum FilePathStyle {
+    /// `/dir/file`, path is a sequence of bytes
+    Posix,
+    /// `C:\dir\file` or `\\server\share\file`, `/` is accepted as separator, path must be UTF-8
+    Windows
+}
+
+impl FilePathStyle {
+    /// Returns style of the platform the crate is compiled for
+    pub fn native() -> FilePathStyle {
+        if cfg!(windows) {
+            FilePathStyle::Windows
+        } else {
+            FilePathStyle::Posix
+        }
+    }
+}
+
+impl UrlComponents {
+    /// Converts absolute local path into `file:` URL, see [RFC 8089](https://www.rfc-editor.org/rfc/rfc8089)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{build_url, UrlComponents};
+    ///
+    /// # #[cfg(unix)] {
+    /// let url_components = UrlComponents::from_file_path("/tmp/a b#1.txt").unwrap();
+    /// assert_eq!("file:///tmp/a%20b%231.txt", build_url(url_components).unwrap());
+    ///
+    /// assert_eq!("error: file path is not absolute: tmp", UrlComponents::from_file_path("tmp").err().unwrap());
+    /// # }
+    /// ```
+    pub fn from_file_path<P: AsRef<Path>>(path: P) -> Result<UrlComponents, String> {
+        UrlComponents::from_file_path_bytes(native_path_bytes(path.as_ref())?.as_slice(), FilePathStyle::native())
+    }
+
+    /// Same as [UrlComponents::from_file_path], but URL path always ends with `/`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::UrlComponents;
+    ///
+    /// # #[cfg(unix)] {
+    /// assert_eq!("/var/www/", UrlComponents::from_directory_path("/var/www").unwrap().path);
+    /// assert_eq!("/var/www/", UrlComponents::from_directory_path("/var/www/").unwrap().path);
+    /// # }
+    /// ```
+    pub fn from_directory_path<P: AsRef<Path>>(path: P) -> Result<UrlComponents, String> {
+        let mut url_components = UrlComponents::from_file_path(path)?;
+        if !url_components.path.ends_with('/') {
+            url_components.path = [url_components.path.as_str(), "/"].join("");
+        }
+        Ok(url_components)
+    }
+
+    /// Converts `file:` URL into local path, query and fragment are ignored
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use url_build_parse::{parse_url, UrlComponents};
+    ///
+    /// # #[cfg(unix)] {
+    /// let url_components = parse_url("file://localhost/tmp/a%20b.txt").unwrap();
+    /// assert_eq!(PathBuf::from("/tmp/a b.txt"), url_components.to_file_path().unwrap());
+    /// # }
+    /// ```
+    pub fn to_file_path(&self) -> Result<PathBuf, String> {
+        path_from_native_bytes(self.to_file_path_bytes(FilePathStyle::native())?)
+    }
+
+    /// Converts absolute path given as bytes into `file:` URL using given path style
+    ///
+    /// Windows drive letter path `C:\dir` becomes `file:///C:/dir`, UNC path `\\server\share\dir`
+    /// becomes `file://server/share/dir`, `\\?\` prefix is accepted. Bytes not allowed in URL path,
+    /// including non-UTF-8 ones, are percent-encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{build_url, FilePathStyle, UrlComponents};
+    ///
+    /// let url_components = UrlComponents::from_file_path_bytes(br"C:\Program Files\f.txt", FilePathStyle::Windows).unwrap();
+    /// assert_eq!("file:///C:/Program%20Files/f.txt", build_url(url_components).unwrap());
+    ///
+    /// let url_components = UrlComponents::from_file_path_bytes(br"\\server\share\f.txt", FilePathStyle::Windows).unwrap();
+    /// assert_eq!("file://server/share/f.txt", build_url(url_components).unwrap());
+    ///
+    /// let url_components = UrlComponents::from_file_path_bytes(b"/tmp/caf\xE9", FilePathStyle::Posix).unwrap();
+    /// assert_eq!("file:///tmp/caf%E9", build_url(url_components).unwrap());
+    /// ```
+    pub fn from_file_path_bytes(path: &[u8], style: FilePathStyle) -> Result<UrlComponents, String> {
+        let (host, url_path) = match style {
+            FilePathStyle::Posix => {
+                if !path.starts_with(b"/") {
+                    return Err(["error: file path is not absolute: ", String::from_utf8_lossy(path).as_ref()].join(""));
+                }
+                ("".to_string(), percent_encode_bytes(path, is_path_char))
+            },
+            FilePathStyle::Windows => windows_path_to_url_path(path)?,
+        };
+
+        Ok(UrlComponents {
+            scheme: "file".to_string(),
+            authority: Some(UrlAuthority { user_info: None, host, port: None }),
+            path: url_path,
+            ..Default::default()
+        })
+    }
+
+    /// Converts `file:` URL into path bytes using given path style
+    ///
+    /// POSIX paths accept only empty or `localhost` host. Windows paths are built from
+    /// drive letter (`/C:/` or legacy `/C|/`) or, when host is given, as UNC path. Path is decoded
+    /// segment by segment, a segment decoding to NUL or separator (`/`, on Windows also `\`) is rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{parse_url, FilePathStyle};
+    ///
+    /// let url_components = parse_url("file:///C:/Program%20Files/f.txt").unwrap();
+    /// assert_eq!(br"C:\Program Files\f.txt".to_vec(), url_components.to_file_path_bytes(FilePathStyle::Windows).unwrap());
+    ///
+    /// let url_components = parse_url("file://server/share/f.txt").unwrap();
+    /// assert_eq!(br"\\server\share\f.txt".to_vec(), url_components.to_file_path_bytes(FilePathStyle::Windows).unwrap());
+    /// assert_eq!(
+    ///     "error: file URL host is not supported for POSIX path: server",
+    ///     url_components.to_file_path_bytes(FilePathStyle::Posix).err().unwrap()
+    /// );
+    /// ```
+    pub fn to_file_path_bytes(&self, style: FilePathStyle) -> Result<Vec<u8>, String> {
+        if !self.scheme.eq_ignore_ascii_case("file") {
+            return Err(["error: not a file URL, scheme is ", self.scheme.as_str()].join(""));
+        }
+
+        let mut host = "";
+        if let Some(authority) = &self.authority {
+            if authority.user_info.is_some() || authority.port.is_some() {
+                return Err("error: file URL must not have user info or port".to_string());
+            }
+            host = authority.host.as_str();
+        }
+        if host.eq_ignore_ascii_case("localhost") {
+            host = "";
+        }
+
+        match style {
+            FilePathStyle::Posix => {
+                if !host.is_empty() {
+                    return Err(["error: file URL host is not supported for POSIX path: ", host].join(""));
+                }
+                if !self.path.starts_with('/') {
+                    return Err(["error: file URL path is not absolute: ", self.path.as_str()].join(""));
+                }
+                decode_path_segments(self.path.as_str(), |byte| byte == b'/')
+            },
+            FilePathStyle::Windows => url_path_to_windows_path(host, self.path.as_str()),
+        }
+    }
+}
+
+fn windows_path_to_url_path(path: &[u8]) -> Result<(String, String), String> {
+    let boxed_path = std::str::from_utf8(path);
+    if boxed_path.is_err() {
+        return Err("error: Windows file path is not valid UTF-8".to_string());
+    }
+    let path = boxed_path.unwrap().replace('\\', "/");
+
+    let mut unprefixed_path = path.to_string();
+    if let Some(verbatim_path) = path.strip_prefix("//?/") {
+        unprefixed_path = verbatim_path.to_string();
+        if let Some(unc_path) = verbatim_path.strip_prefix("UNC/") {
+            unprefixed_path = ["//", unc_path].join("");
+        }
+    }
+    let unprefixed_path = unprefixed_path.as_str();
+
+    if is_drive_letter_path(unprefixed_path) {
+        let encoded = percent_encode_bytes(unprefixed_path.as_bytes(), is_path_char);
+        return Ok(("".to_string(), ["/", encoded.as_str()].join("")));
+    }
+
+    if let Some(unc_path) = unprefixed_path.strip_prefix("//") {
+        let (host, share_path) = unc_path.split_once('/').unwrap_or((unc_path, ""));
+        if host.is_empty() || host == "." || !is_valid_host(host) {
+            return Err(["error: UNC path has invalid server name: ", host].join(""));
+        }
+        let encoded = percent_encode_bytes(share_path.as_bytes(), is_path_char);
+        return Ok((host.to_string(), ["/", encoded.as_str()].join("")));
+    }
+
+    Err(["error: file path is not absolute: ", path.as_str()].join(""))
+}
+
+fn url_path_to_windows_path(host: &str, url_path: &str) -> Result<Vec<u8>, String> {
+    let decoded_path = decode_path_segments(url_path, |byte| byte == b'/' || byte == b'\\')?;
+    let boxed_path = String::from_utf8(decoded_path);
+    if boxed_path.is_err() {
+        return Err(["error: file URL path is not valid UTF-8: ", url_path].join(""));
+    }
+    let path = boxed_path.unwrap();
+
+    if !host.is_empty() {
+        let share_path = path.strip_prefix('/').unwrap_or(path.as_str());
+        return Ok(["\\\\", host, "\\", share_path.replace('/', "\\").as_str()].join("").into_bytes());
+    }
+
+    let drive_path = path.strip_prefix('/').unwrap_or(path.as_str());
+    if !is_drive_letter_path(drive_path) {
+        return Err(["error: file URL path has no drive letter: ", url_path].join(""));
+    }
+
+    let drive = [&drive_path[..1], ":"].join("");
+    let mut rest = drive_path[2..].replace('/', "\\");
+    if rest.is_empty() {
+        rest = "\\".to_string();
+    }
+    Ok([drive, rest].join("").into_bytes())
+}
+
+fn decode_path_segments(url_path: &str, is_separator: fn(u8) -> bool) -> Result<Vec<u8>, String> {
+    let mut path : Vec<u8> = vec![];
+    for (index, segment) in url_path.split('/').enumerate() {
+        if index > 0 {
+            path.push(b'/');
+        }
+        let decoded_segment = percent_decode(segment);
+        if decoded_segment.iter().any(|byte| *byte == 0 || is_separator(*byte)) {
+            return Err(["error: file URL path segment decodes to NUL or separator: ", url_path].join(""));
+        }
+        path.extend(decoded_segment);
+    }
+    Ok(path)
+}
+
+fn is_drive_letter_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2
+        && bytes[0].is_ascii_alphabetic()
+        && (bytes[1] == b':' || bytes[1] == b'|')
+        && (bytes.len() == 2 || bytes[2] == b'/')
+}
+
+#[cfg(unix)]
+fn native_path_bytes(path: &Path) -> Result<Vec<u8>, String> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(path.as_os_str().as_bytes().to_vec())
+}
+
+#[cfg(not(unix))]
+fn native_path_bytes(path: &Path) -> Result<Vec<u8>, String> {
+    let boxed_path = path.to_str();
+    if boxed_path.is_none() {
+        return Err(["error: file path is not valid Unicode: ", path.to_string_lossy().as_ref()].join(""));
+    }
+    Ok(boxed_path.unwrap().as_bytes().to_vec())
+}
+
+#[cfg(unix)]
+fn path_from_native_bytes(bytes: Vec<u8>) -> Result<PathBuf, String> {
+    use std::os::unix::ffi::OsStringExt;
+    Ok(PathBuf::from(std::ffi::OsString::from_vec(bytes)))
+}
+
+#[cfg(not(unix))]
+fn path_from_native_bytes(bytes: Vec<u8>) -> Result<PathBuf, String> {
+    let boxed_path = String::from_utf8(bytes);
+    if boxed_path.is_err() {
+        return Err("error: file URL path is not valid UTF-8".to_string());
+    }
+    Ok(PathBuf::from(boxed_path.unwrap()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_url, parse_url, FilePathStyle, UrlComponents};
+
+    #[test]
+    fn posix_path_round_trip() {
+        let list : Vec<(&[u8], &str)> = vec![
+            (b"/", "file:///"),
+            (b"/etc/hosts", "file:///etc/hosts"),
+            (b"/tmp/50%?x#y", "file:///tmp/50%25%3Fx%23y"),
+            (b"/tmp/\xD0\xB4\xFF", "file:///tmp/%D0%B4%FF"),
+            (b"/a/b\\c:d;e=f", "file:///a/b%5Cc:d;e=f"),
+        ];
+
+        for (path, url) in list {
+            let url_components = UrlComponents::from_file_path_bytes(path, FilePathStyle::Posix).unwrap();
+            assert_eq!(url, build_url(url_components).unwrap());
+            assert_eq!(path.to_vec(), parse_url(url).unwrap().to_file_path_bytes(FilePathStyle::Posix).unwrap());
+        }
+    }
+
+    #[test]
+    fn windows_path_round_trip() {
+        let list = vec![
+            (r"C:\", "file:///C:/"),
+            (r"c:\dir\f.txt", "file:///c:/dir/f.txt"),
+            (r"D:\Документы\50%.txt", "file:///D:/%D0%94%D0%BE%D0%BA%D1%83%D0%BC%D0%B5%D0%BD%D1%82%D1%8B/50%25.txt"),
+            (r"\\server\share\dir\f.txt", "file://server/share/dir/f.txt"),
+            (r"\\server\share", "file://server/share"),
+        ];
+
+        for (path, url) in list {
+            let url_components = UrlComponents::from_file_path_bytes(path.as_bytes(), FilePathStyle::Windows).unwrap();
+            assert_eq!(url, build_url(url_components).unwrap());
+            assert_eq!(path.as_bytes().to_vec(), parse_url(url).unwrap().to_file_path_bytes(FilePathStyle::Windows).unwrap());
+        }
+    }
+
+    #[test]
+    fn windows_path_variants() {
+        let list = vec![
+            ("C:/dir/f.txt", "file:///C:/dir/f.txt"),
+            ("C:", "file:///C:"),
+            (r"\\?\C:\dir", "file:///C:/dir"),
+            (r"\\?\UNC\server\share\f", "file://server/share/f"),
+        ];
+        for (path, url) in list {
+            let url_components = UrlComponents::from_file_path_bytes(path.as_bytes(), FilePathStyle::Windows).unwrap();
+            assert_eq!(url, build_url(url_components).unwrap());
+        }
+
+        let list = vec![
+            ("file:///C|/dir", r"C:\dir"),
+            ("file:///C:", r"C:\"),
+            ("file://localhost/C:/dir/", r"C:\dir\"),
+            ("file:/C:/dir", r"C:\dir"),
+        ];
+        for (url, path) in list {
+            assert_eq!(path.as_bytes().to_vec(), parse_url(url).unwrap().to_file_path_bytes(FilePathStyle::Windows).unwrap());
+        }
+    }
+
+    #[test]
+    fn file_path_errors() {
+        let list : Vec<(&[u8], FilePathStyle, &str)> = vec![
+            (b"tmp/f", FilePathStyle::Posix, "error: file path is not absolute: tmp/f"),
+            (br"dir\f", FilePathStyle::Windows, "error: file path is not absolute: dir/f"),
+            (b"C:dir", FilePathStyle::Windows, "error: file path is not absolute: C:dir"),
+            (br"\\.\pipe\x", FilePathStyle::Windows, "error: UNC path has invalid server name: ."),
+            (br"\\\share", FilePathStyle::Windows, "error: UNC path has invalid server name: "),
+            (b"C:\\\xFF", FilePathStyle::Windows, "error: Windows file path is not valid UTF-8"),
+        ];
+        for (path, style, error) in list {
+            assert_eq!(error, UrlComponents::from_file_path_bytes(path, style).err().unwrap());
+        }
+
+        let list = vec![
+            ("https://host/f", FilePathStyle::Posix, "error: not a file URL, scheme is https"),
+            ("file://usr@host/f", FilePathStyle::Posix, "error: file URL must not have user info or port"),
+            ("file://host/f", FilePathStyle::Posix, "error: file URL host is not supported for POSIX path: host"),
+            ("file:///dir/f", FilePathStyle::Windows, "error: file URL path has no drive letter: /dir/f"),
+            ("file:///C:/%FF", FilePathStyle::Windows, "error: file URL path is not valid UTF-8: /C:/%FF"),
+            ("file:///tmp/%2F..", FilePathStyle::Posix, "error: file URL path segment decodes to NUL or separator: /tmp/%2F.."),
+            ("file:///tmp/a%00b", FilePathStyle::Posix, "error: file URL path segment decodes to NUL or separator: /tmp/a%00b"),
+            ("file://server/share/%5C..%5Cx", FilePathStyle::Windows, "error: file URL path segment decodes to NUL or separator: /share/%5C..%5Cx"),
+            ("file:///C:/a%2Fb", FilePathStyle::Windows, "error: file URL path segment decodes to NUL or separator: /C:/a%2Fb"),
+            ("file:///C:/a%00b", FilePathStyle::Windows, "error: file URL path segment decodes to NUL or separator: /C:/a%00b"),
+        ];
+        for (url, style, error) in list {
+            assert_eq!(error, parse_url(url).unwrap().to_file_path_bytes(style).err().unwrap());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn native_path_conversion() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        use std::path::Path;
+
+        let path = Path::new(OsStr::from_bytes(b"/tmp/\xFFdir"));
+        let url_components = UrlComponents::from_directory_path(path).unwrap();
+        assert_eq!("file:///tmp/%FFdir/", build_url(url_components.clone()).unwrap());
+        assert_eq!(Path::new(OsStr::from_bytes(b"/tmp/\xFFdir/")), url_components.to_file_path().unwrap());
+    }
+}
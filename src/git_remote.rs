@@ -0,0 +1,490 @@
+use crate::percent_encoding::{is_path_char, percent_decode_to_string, percent_encode};
+use crate::validation::is_valid_host;
+use crate::{build_url, parse_url, UrlAuthority, UrlComponents, UrlUserInfo};
+
+/// Syntax the git remote was written in
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GitRemoteKind {
+    /// `[user@]host:path`
+    ScpLike,
+    /// `ssh://`, `git://`, `http://`, `https://` or `file://` URL
+    Url,
+    /// local path, like `/srv/repo.git`, `../repo` or `C:\repo`
+    LocalPath
+}
+
+/// Git remote normalized into UrlComponents
+///
+/// scp-like remote becomes `ssh` URL the way git resolves it: path relative to the home directory
+/// gets `/~/` prepended and absolute path is kept, so `git@host:org/repo.git` is `ssh://git@host/~/org/repo.git`
+/// and `host:/srv/repo.git` is `ssh://host/srv/repo.git`. Local path has empty scheme and no authority,
+/// path is percent-encoded.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct GitRemote {
+    pub kind: GitRemoteKind,
+    pub url_components: UrlComponents
+}
+
+impl GitRemote {
+    /// Returns percent-decoded path parts, local paths are also split by `\`,
+    /// leading home directory `~` of the remote path is skipped
+    pub fn path_segments(&self) -> Vec<String> {
+        let path = percent_decode_to_string(self.url_components.path.as_str());
+        let mut segments : Vec<String> = path.split(|char| char == '/' || (char == '\\' && self.kind == GitRemoteKind::LocalPath))
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| segment.to_string())
+            .collect();
+        if self.kind != GitRemoteKind::LocalPath && segments.first().is_some_and(|segment| segment == "~") {
+            segments.remove(0);
+        }
+        segments
+    }
+
+    /// Returns all path parts before repository name joined by `/`, like `org` or `group/subgroup`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_git_remote;
+    ///
+    /// let git_remote = parse_git_remote("https://gitlab.com/group/subgroup/project.git").unwrap();
+    /// assert_eq!("group/subgroup", git_remote.owner().unwrap());
+    /// assert_eq!("project", git_remote.repository().unwrap());
+    /// ```
+    pub fn owner(&self) -> Option<String> {
+        let segments = self.path_segments();
+        if segments.len() < 2 {
+            return None;
+        }
+        Some(segments[..segments.len() - 1].join("/"))
+    }
+
+    /// Returns last path part without `.git` suffix
+    pub fn repository(&self) -> Option<String> {
+        let segments = self.path_segments();
+        let last_segment = segments.last()?;
+        let repository = last_segment.strip_suffix(".git").unwrap_or(last_segment.as_str());
+        if repository.is_empty() {
+            return None;
+        }
+        Some(repository.to_string())
+    }
+
+    /// Converts scp-like remote into `ssh://` URL form, `ssh` URL is returned as is
+    ///
+    /// Home-relative `host:org/repo.git` becomes `ssh://host/~/org/repo.git`, see [GitRemote].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{build_git_remote, parse_git_remote};
+    ///
+    /// let git_remote = parse_git_remote("git@github.com:org/repo.git").unwrap();
+    /// assert_eq!("ssh://git@github.com/~/org/repo.git", build_git_remote(&git_remote.to_ssh_url().unwrap()).unwrap());
+    /// ```
+    pub fn to_ssh_url(&self) -> Result<GitRemote, String> {
+        if !self.is_ssh() {
+            return Err(["error: git remote is not an ssh remote: ", build_git_remote(self)?.as_str()].join(""));
+        }
+        Ok(GitRemote { kind: GitRemoteKind::Url, url_components: self.url_components.clone() })
+    }
+
+    /// Converts `ssh://` URL into scp-like form, port cannot be written in scp-like form
+    ///
+    /// `ssh://host/~/org/repo.git` becomes home-relative `host:org/repo.git`,
+    /// `ssh://host/srv/repo.git` becomes absolute `host:/srv/repo.git`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{build_git_remote, parse_git_remote};
+    ///
+    /// let git_remote = parse_git_remote("ssh://git@github.com/~/org/repo.git").unwrap();
+    /// assert_eq!("git@github.com:org/repo.git", build_git_remote(&git_remote.to_scp_like().unwrap()).unwrap());
+    ///
+    /// let git_remote = parse_git_remote("ssh://git@host/srv/repo.git").unwrap();
+    /// assert_eq!("git@host:/srv/repo.git", build_git_remote(&git_remote.to_scp_like().unwrap()).unwrap());
+    ///
+    /// let git_remote = parse_git_remote("ssh://git@host:2222/repo.git").unwrap();
+    /// assert_eq!("error: scp-like git remote cannot have port: 2222", git_remote.to_scp_like().err().unwrap());
+    /// ```
+    pub fn to_scp_like(&self) -> Result<GitRemote, String> {
+        if !self.is_ssh() {
+            return Err(["error: git remote is not an ssh remote: ", build_git_remote(self)?.as_str()].join(""));
+        }
+
+        let authority = self.url_components.authority.as_ref().unwrap();
+        if let Some(port) = authority.port {
+            return Err(["error: scp-like git remote cannot have port: ", port.to_string().as_str()].join(""));
+        }
+        if authority.user_info.as_ref().is_some_and(|user_info| user_info.password.is_some()) {
+            return Err("error: scp-like git remote cannot have password".to_string());
+        }
+        if self.url_components.path.len() < 2 {
+            return Err(["error: scp-like git remote requires path: ", build_git_remote(self)?.as_str()].join(""));
+        }
+
+        Ok(GitRemote { kind: GitRemoteKind::ScpLike, url_components: self.url_components.clone() })
+    }
+
+    fn is_ssh(&self) -> bool {
+        self.kind != GitRemoteKind::LocalPath
+            && self.url_components.authority.is_some()
+            && is_ssh_scheme(self.url_components.scheme.as_str())
+    }
+}
+
+/// Parses git remote written as scp-like `[user@]host:path`, URL or local path
+///
+/// Remote is scp-like when it has no `://` and `:` comes before the first `/`,
+/// single letter before `:` is Windows drive letter, not a host. scp-like remote must have path.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{parse_git_remote, GitRemoteKind};
+///
+/// let git_remote = parse_git_remote("git@github.com:org/repo.git").unwrap();
+/// assert_eq!(GitRemoteKind::ScpLike, git_remote.kind);
+/// assert_eq!("ssh", git_remote.url_components.scheme);
+/// assert_eq!("github.com", git_remote.url_components.authority.as_ref().unwrap().host);
+/// assert_eq!("/~/org/repo.git", git_remote.url_components.path);
+/// assert_eq!("org", git_remote.owner().unwrap());
+/// assert_eq!("repo", git_remote.repository().unwrap());
+///
+/// let git_remote = parse_git_remote("https://github.com/org/repo").unwrap();
+/// assert_eq!(GitRemoteKind::Url, git_remote.kind);
+/// assert_eq!("repo", git_remote.repository().unwrap());
+///
+/// let git_remote = parse_git_remote(r"C:\repos\repo.git").unwrap();
+/// assert_eq!(GitRemoteKind::LocalPath, git_remote.kind);
+/// assert_eq!("repo", git_remote.repository().unwrap());
+/// ```
+pub fn parse_git_remote(remote: &str) -> Result<GitRemote, String> {
+    if remote.is_empty() {
+        return Err("error: git remote is empty".to_string());
+    }
+
+    if remote.contains("://") {
+        let url_components = parse_url(remote)?;
+        let scheme = url_components.scheme.to_ascii_lowercase();
+        let is_supported = is_ssh_scheme(scheme.as_str()) || matches!(scheme.as_str(), "git" | "http" | "https" | "file");
+        if !is_supported {
+            return Err(["error: git remote scheme is not supported: ", url_components.scheme.as_str()].join(""));
+        }
+        if url_components.authority.is_none() || (scheme != "file" && url_components.authority.as_ref().unwrap().host.is_empty()) {
+            return Err(["error: git remote URL has no host: ", remote].join(""));
+        }
+        return Ok(GitRemote { kind: GitRemoteKind::Url, url_components });
+    }
+
+    if let Some((user, host, path)) = split_scp_like(remote) {
+        if !is_valid_host(host) {
+            return Err(["error: git remote host is not valid: ", host].join(""));
+        }
+        if path.is_empty() {
+            return Err(["error: scp-like git remote has no path: ", remote].join(""));
+        }
+        let user_info = user.map(|user| UrlUserInfo { username: user.to_string(), password: None });
+
+        return Ok(GitRemote {
+            kind: GitRemoteKind::ScpLike,
+            url_components: UrlComponents {
+                scheme: "ssh".to_string(),
+                authority: Some(UrlAuthority { user_info, host: host.to_string(), port: None }),
+                path: percent_encode(scp_path_to_url_path(path).as_str(), is_path_char),
+                ..Default::default()
+            }
+        });
+    }
+
+    Ok(GitRemote {
+        kind: GitRemoteKind::LocalPath,
+        url_components: UrlComponents {
+            path: percent_encode(remote, is_path_char),
+            ..Default::default()
+        }
+    })
+}
+
+/// Builds git remote in the syntax given by its kind
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_git_remote, parse_git_remote};
+///
+/// let list = vec![
+///     "git@github.com:org/repo.git",
+///     "ssh://git@github.com:22/org/repo.git",
+///     "git://example.org/repo.git",
+///     "../repo",
+/// ];
+///
+/// for remote in list {
+///     assert_eq!(remote, build_git_remote(&parse_git_remote(remote).unwrap()).unwrap());
+/// }
+/// ```
+pub fn build_git_remote(git_remote: &GitRemote) -> Result<String, String> {
+    match git_remote.kind {
+        GitRemoteKind::Url => build_url(git_remote.url_components.clone()),
+        GitRemoteKind::LocalPath => Ok(percent_decode_to_string(git_remote.url_components.path.as_str())),
+        GitRemoteKind::ScpLike => {
+            let boxed_authority = git_remote.url_components.authority.as_ref();
+            if boxed_authority.is_none() {
+                return Err("error: scp-like git remote has no host".to_string());
+            }
+            let authority = boxed_authority.unwrap();
+
+            let mut remote = "".to_string();
+            if let Some(user_info) = &authority.user_info {
+                remote = [user_info.username.as_str(), "@"].join("");
+            }
+            let path = url_path_to_scp_path(percent_decode_to_string(git_remote.url_components.path.as_str()).as_str());
+            Ok([remote.as_str(), authority.host.as_str(), ":", path.as_str()].join(""))
+        },
+    }
+}
+
+fn is_ssh_scheme(scheme: &str) -> bool {
+    matches!(scheme.to_ascii_lowercase().as_str(), "ssh" | "git+ssh" | "ssh+git")
+}
+
+fn scp_path_to_url_path(path: &str) -> String {
+    if path.starts_with('/') {
+        return path.to_string();
+    }
+    if path.starts_with('~') {
+        return ["/", path].join("");
+    }
+    ["/~/", path].join("")
+}
+
+fn url_path_to_scp_path(path: &str) -> String {
+    if let Some(home_path) = path.strip_prefix("/~/").filter(|home_path| !home_path.is_empty()) {
+        return home_path.to_string();
+    }
+    if let Some(user_home_path) = path.strip_prefix("/~") {
+        return ["~", user_home_path].join("");
+    }
+    path.to_string()
+}
+
+fn split_scp_like(remote: &str) -> Option<(Option<&str>, &str, &str)> {
+    let mut user : Option<&str> = None;
+    let mut host_and_path = remote;
+    if let Some((user_part, rest)) = remote.split_once('@') {
+        if !user_part.is_empty() && !user_part.contains(['/', ':', '\\']) {
+            user = Some(user_part);
+            host_and_path = rest;
+        }
+    }
+
+    let colon = if host_and_path.starts_with('[') {
+        host_and_path.find("]:")? + 1
+    } else {
+        host_and_path.find(':')?
+    };
+    let boxed_slash = host_and_path.find(['/', '\\']);
+    if boxed_slash.is_some() && boxed_slash.unwrap() < colon {
+        return None;
+    }
+
+    let host = &host_and_path[..colon];
+    let path = &host_and_path[colon + 1..];
+    if host.is_empty() || (user.is_none() && host.len() == 1 && host.as_bytes()[0].is_ascii_alphabetic()) {
+        return None;
+    }
+
+    Some((user, host, path))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_git_remote, build_url, parse_git_remote, GitRemoteKind};
+
+    #[test]
+    fn parse_git_remote_scp_like() {
+        let git_remote = parse_git_remote("git@github.com:org/repo.git").unwrap();
+
+        assert_eq!(GitRemoteKind::ScpLike, git_remote.kind);
+        assert_eq!("ssh://git@github.com/~/org/repo.git", build_url(git_remote.url_components.clone()).unwrap());
+        assert_eq!("git@github.com:org/repo.git", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_scp_like_absolute_and_home_paths() {
+        let git_remote = parse_git_remote("host.xz:/srv/repo.git").unwrap();
+        assert_eq!("ssh://host.xz/srv/repo.git", build_url(git_remote.url_components.clone()).unwrap());
+        assert_eq!("host.xz:/srv/repo.git", build_git_remote(&git_remote).unwrap());
+
+        let git_remote = parse_git_remote("host.xz:~/repo.git").unwrap();
+        assert_eq!("ssh://host.xz/~/repo.git", build_url(git_remote.url_components.clone()).unwrap());
+        assert_eq!("host.xz:repo.git", build_git_remote(&git_remote).unwrap());
+
+        let git_remote = parse_git_remote("host.xz:~user/repo.git").unwrap();
+        assert_eq!("ssh://host.xz/~user/repo.git", build_url(git_remote.url_components.clone()).unwrap());
+        assert_eq!("host.xz:~user/repo.git", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_scp_like_ip_literal_host() {
+        let git_remote = parse_git_remote("git@[::1]:repo.git").unwrap();
+
+        assert_eq!(GitRemoteKind::ScpLike, git_remote.kind);
+        assert_eq!("ssh://git@[::1]/~/repo.git", build_url(git_remote.url_components.clone()).unwrap());
+        assert_eq!("git@[::1]:repo.git", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_scp_like_encodes_path() {
+        let git_remote = parse_git_remote("git@host:dir/a b%.git").unwrap();
+
+        assert_eq!("ssh://git@host/~/dir/a%20b%25.git", build_url(git_remote.url_components.clone()).unwrap());
+        assert_eq!("git@host:dir/a b%.git", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_ssh_url() {
+        let git_remote = parse_git_remote("ssh://git@github.com/org/repo.git").unwrap();
+
+        assert_eq!(GitRemoteKind::Url, git_remote.kind);
+        assert_eq!("ssh://git@github.com/org/repo.git", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_git_ssh_url() {
+        let git_remote = parse_git_remote("git+ssh://host/repo").unwrap();
+
+        assert_eq!(GitRemoteKind::Url, git_remote.kind);
+        assert_eq!("git+ssh://host/repo", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_git_protocol_url() {
+        let git_remote = parse_git_remote("git://example.org/~user/repo.git").unwrap();
+
+        assert_eq!(GitRemoteKind::Url, git_remote.kind);
+        assert_eq!("git://example.org/~user/repo.git", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_file_url() {
+        let git_remote = parse_git_remote("file:///srv/repo.git").unwrap();
+
+        assert_eq!(GitRemoteKind::Url, git_remote.kind);
+        assert_eq!("file:///srv/repo.git", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_local_paths() {
+        let git_remote = parse_git_remote("/srv/repo.git").unwrap();
+        assert_eq!(GitRemoteKind::LocalPath, git_remote.kind);
+        assert_eq!("/srv/repo.git", git_remote.url_components.path);
+        assert_eq!("/srv/repo.git", build_git_remote(&git_remote).unwrap());
+
+        let git_remote = parse_git_remote("./a:b").unwrap();
+        assert_eq!(GitRemoteKind::LocalPath, git_remote.kind);
+        assert_eq!("./a:b", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_windows_paths() {
+        let git_remote = parse_git_remote("C:/repos/repo").unwrap();
+        assert_eq!(GitRemoteKind::LocalPath, git_remote.kind);
+        assert_eq!("C:/repos/repo", build_git_remote(&git_remote).unwrap());
+
+        let git_remote = parse_git_remote(r"\\server\share\repo").unwrap();
+        assert_eq!(GitRemoteKind::LocalPath, git_remote.kind);
+        assert_eq!("%5C%5Cserver%5Cshare%5Crepo", git_remote.url_components.path);
+        assert_eq!(r"\\server\share\repo", build_git_remote(&git_remote).unwrap());
+    }
+
+    #[test]
+    fn git_remote_owner_and_repository() {
+        let git_remote = parse_git_remote("git@github.com:rust-lang/rust.git").unwrap();
+        assert_eq!(vec!["rust-lang", "rust.git"], git_remote.path_segments());
+        assert_eq!("rust-lang", git_remote.owner().unwrap());
+        assert_eq!("rust", git_remote.repository().unwrap());
+
+        let git_remote = parse_git_remote("ssh://git@github.com/rust-lang/rust.git").unwrap();
+        assert_eq!("rust-lang", git_remote.owner().unwrap());
+        assert_eq!("rust", git_remote.repository().unwrap());
+
+        let git_remote = parse_git_remote(r"..\work\tool").unwrap();
+        assert_eq!("../work", git_remote.owner().unwrap());
+        assert_eq!("tool", git_remote.repository().unwrap());
+
+        let git_remote = parse_git_remote("https://example.com/").unwrap();
+        assert!(git_remote.owner().is_none());
+        assert!(git_remote.repository().is_none());
+    }
+
+    #[test]
+    fn git_remote_conversion() {
+        let git_remote = parse_git_remote("git@github.com:org/repo.git").unwrap();
+        assert_eq!("ssh://git@github.com/~/org/repo.git", build_git_remote(&git_remote.to_ssh_url().unwrap()).unwrap());
+
+        let git_remote = parse_git_remote("ssh://git@github.com/~/org/repo.git").unwrap();
+        assert_eq!("git@github.com:org/repo.git", build_git_remote(&git_remote.to_scp_like().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn git_remote_conversion_absolute_path() {
+        let git_remote = parse_git_remote("host:/srv/repo.git").unwrap();
+        assert_eq!("ssh://host/srv/repo.git", build_git_remote(&git_remote.to_ssh_url().unwrap()).unwrap());
+
+        let git_remote = parse_git_remote("ssh://host/srv/repo.git").unwrap();
+        assert_eq!("host:/srv/repo.git", build_git_remote(&git_remote.to_scp_like().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn git_remote_conversion_home_path() {
+        let git_remote = parse_git_remote("host:~/repo.git").unwrap();
+        assert_eq!("ssh://host/~/repo.git", build_git_remote(&git_remote.to_ssh_url().unwrap()).unwrap());
+
+        let git_remote = parse_git_remote("ssh://host/~/repo.git").unwrap();
+        assert_eq!("host:repo.git", build_git_remote(&git_remote.to_scp_like().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn git_remote_conversion_errors() {
+        assert_eq!(
+            "error: git remote is not an ssh remote: https://github.com/org/repo",
+            parse_git_remote("https://github.com/org/repo").unwrap().to_scp_like().err().unwrap()
+        );
+        assert_eq!(
+            "error: scp-like git remote requires path: ssh://host",
+            parse_git_remote("ssh://host").unwrap().to_scp_like().err().unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_git_remote_empty() {
+        let boxed_git_remote = parse_git_remote("");
+
+        assert!(boxed_git_remote.is_err());
+        assert_eq!("error: git remote is empty", boxed_git_remote.err().unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_unsupported_scheme() {
+        let boxed_git_remote = parse_git_remote("ftp://host/repo.git");
+
+        assert!(boxed_git_remote.is_err());
+        assert_eq!("error: git remote scheme is not supported: ftp", boxed_git_remote.err().unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_invalid_host() {
+        assert_eq!("error: git remote URL has no host: https:///repo.git", parse_git_remote("https:///repo.git").err().unwrap());
+        assert_eq!("error: git remote host is not valid: bad host", parse_git_remote("git@bad host:repo").err().unwrap());
+    }
+
+    #[test]
+    fn parse_git_remote_scp_like_without_path() {
+        assert_eq!("error: scp-like git remote has no path: git@host:", parse_git_remote("git@host:").err().unwrap());
+        assert_eq!("error: scp-like git remote has no path: host:", parse_git_remote("host:").err().unwrap());
+    }
+}
@@ -0,0 +1,390 @@
+use crate::percent_encoding::{is_path_char, is_pchar, percent_decode_to_string, percent_encode};
+use crate::validation::is_valid_host;
+use crate::parse_authority;
+
+/// Search scope of the LDAP URL
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LdapScope {
+    Base,
+    One,
+    Sub
+}
+
+impl LdapScope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LdapScope::Base => "base",
+            LdapScope::One => "one",
+            LdapScope::Sub => "sub",
+        }
+    }
+}
+
+/// LDAP URL extension, `critical` is true when extension is written with leading `!`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LdapExtension {
+    pub critical: bool,
+    pub name: String,
+    pub value: Option<String>
+}
+
+/// Decoded LDAP URL, see [RFC 4516](https://www.rfc-editor.org/rfc/rfc4516)
+///
+/// - `scheme`: `ldap`, `ldaps` or `ldapi` as written
+/// - `host`, `port`: server, empty host means client default
+/// - `dn`: base distinguished name, percent-decoded
+/// - `attributes`: requested attributes, empty list means all
+/// - `scope`, `filter`: `None` when omitted, see [LdapUrl::scope_or_default] and [LdapUrl::filter_or_default]
+/// - `extensions`: extensions in order, names and values are percent-decoded
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LdapUrl {
+    pub scheme: String,
+    pub host: String,
+    pub port: Option<usize>,
+    pub dn: String,
+    pub attributes: Vec<String>,
+    pub scope: Option<LdapScope>,
+    pub filter: Option<String>,
+    pub extensions: Vec<LdapExtension>
+}
+
+impl LdapUrl {
+    /// Creates `ldap` URL with given host and base DN, everything else is omitted
+    pub fn new(host: &str, dn: &str) -> LdapUrl {
+        LdapUrl {
+            scheme: "ldap".to_string(),
+            host: host.to_string(),
+            port: None,
+            dn: dn.to_string(),
+            attributes: vec![],
+            scope: None,
+            filter: None,
+            extensions: vec![]
+        }
+    }
+
+    /// Returns scope, `base` if omitted
+    pub fn scope_or_default(&self) -> LdapScope {
+        self.scope.unwrap_or(LdapScope::Base)
+    }
+
+    /// Returns filter, `(objectClass=*)` if omitted
+    pub fn filter_or_default(&self) -> &str {
+        self.filter.as_deref().unwrap_or("(objectClass=*)")
+    }
+}
+
+/// Parses LDAP URL in form `ldap://host:port/dn?attributes?scope?filter?extensions`, fragment is not allowed
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{parse_ldap_url, LdapScope};
+///
+/// let ldap_url = parse_ldap_url("ldap://ldap1.example.net:6666/o=University%20of%20Michigan,c=US??sub?(cn=Babs%20Jensen)").unwrap();
+/// assert_eq!("ldap1.example.net", ldap_url.host);
+/// assert_eq!(Some(6666), ldap_url.port);
+/// assert_eq!("o=University of Michigan,c=US", ldap_url.dn);
+/// assert!(ldap_url.attributes.is_empty());
+/// assert_eq!(Some(LdapScope::Sub), ldap_url.scope);
+/// assert_eq!("(cn=Babs Jensen)", ldap_url.filter_or_default());
+///
+/// let ldap_url = parse_ldap_url("ldap://[2001:db8::7]/c=GB?objectClass?one").unwrap();
+/// assert_eq!(vec!["objectClass"], ldap_url.attributes);
+/// assert_eq!(LdapScope::One, ldap_url.scope_or_default());
+/// ```
+pub fn parse_ldap_url(url: &str) -> Result<LdapUrl, String> {
+    let boxed_scheme = url.split_once("://");
+    if boxed_scheme.is_none() {
+        return Err(["error: not an LDAP URL: ", url].join(""));
+    }
+    let (scheme, remaining_url) = boxed_scheme.unwrap();
+    if !matches!(scheme.to_ascii_lowercase().as_str(), "ldap" | "ldaps" | "ldapi") {
+        return Err(["error: not an LDAP URL: ", url].join(""));
+    }
+
+    let (authority, components) = remaining_url.split_once('/').unwrap_or((remaining_url, ""));
+    if authority.contains('?') || authority.contains('#') {
+        return Err(["error: LDAP URL must have '/' before query: ", url].join(""));
+    }
+
+    let mut host = "".to_string();
+    let mut port : Option<usize> = None;
+    if !authority.is_empty() {
+        let (username, _password, parsed_host, parsed_port) = parse_authority(authority)?;
+        if username.is_some() {
+            return Err(["error: LDAP URL must not have user info: ", authority].join(""));
+        }
+        if !is_valid_host(parsed_host.as_str()) {
+            return Err(["error: LDAP URL host is not valid: ", parsed_host.as_str()].join(""));
+        }
+        host = parsed_host;
+        port = parsed_port;
+    }
+
+    if components.contains('#') {
+        return Err(["error: LDAP URL must not have fragment: ", url].join(""));
+    }
+
+    let parts : Vec<&str> = components.split('?').collect();
+    if parts.len() > 5 {
+        return Err(["error: LDAP URL has more than 4 '?' separated components: ", url].join(""));
+    }
+
+    let mut ldap_url = LdapUrl {
+        scheme: scheme.to_string(),
+        port,
+        ..LdapUrl::new(host.as_str(), percent_decode_to_string(parts[0]).as_str())
+    };
+
+    if parts.len() > 1 && !parts[1].is_empty() {
+        ldap_url.attributes = parts[1].split(',').map(percent_decode_to_string).collect();
+    }
+
+    if parts.len() > 2 && !parts[2].is_empty() {
+        ldap_url.scope = Some(match parts[2].to_ascii_lowercase().as_str() {
+            "base" => LdapScope::Base,
+            "one" => LdapScope::One,
+            "sub" => LdapScope::Sub,
+            _ => return Err(["error: LDAP URL scope is not one of base, one, sub: ", parts[2]].join("")),
+        });
+    }
+
+    if parts.len() > 3 && !parts[3].is_empty() {
+        ldap_url.filter = Some(percent_decode_to_string(parts[3]));
+    }
+
+    if parts.len() > 4 && !parts[4].is_empty() {
+        for extension in parts[4].split(',') {
+            let boxed_name = extension.strip_prefix('!');
+            let critical = boxed_name.is_some();
+            let (name, value) = boxed_name.unwrap_or(extension).split_once('=')
+                .map(|(name, value)| (name, Some(percent_decode_to_string(value))))
+                .unwrap_or((boxed_name.unwrap_or(extension), None));
+            if name.is_empty() {
+                return Err(["error: LDAP URL extension has no type: ", extension].join(""));
+            }
+            ldap_url.extensions.push(LdapExtension { critical, name: percent_decode_to_string(name), value });
+        }
+    }
+
+    Ok(ldap_url)
+}
+
+/// Builds LDAP URL, trailing omitted components are not written, `?` is always escaped
+/// and `,` is escaped within attributes and extensions, host must be valid
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_ldap_url, LdapExtension, LdapScope, LdapUrl};
+///
+/// let ldap_url = LdapUrl {
+///     attributes: vec!["cn".to_string(), "mail".to_string()],
+///     scope: Some(LdapScope::Sub),
+///     filter: Some("(sn=O'Brien?)".to_string()),
+///     extensions: vec![LdapExtension { critical: true, name: "bindname".to_string(), value: Some("cn=Manager,o=Foo".to_string()) }],
+///     ..LdapUrl::new("ldap.example.com", "ou=People,dc=example,dc=com")
+/// };
+///
+/// assert_eq!(
+///     "ldap://ldap.example.com/ou=People,dc=example,dc=com?cn,mail?sub?(sn=O'Brien%3F)?!bindname=cn=Manager%2Co=Foo",
+///     build_ldap_url(&ldap_url).unwrap()
+/// );
+/// assert_eq!("ldap:///o=Foo", build_ldap_url(&LdapUrl::new("", "o=Foo")).unwrap());
+/// assert_eq!("error: LDAP URL host is not valid: a/b", build_ldap_url(&LdapUrl::new("a/b", "o=Foo")).err().unwrap());
+/// ```
+pub fn build_ldap_url(ldap_url: &LdapUrl) -> Result<String, String> {
+    if !ldap_url.host.is_empty() && !is_valid_host(ldap_url.host.as_str()) {
+        return Err(["error: LDAP URL host is not valid: ", ldap_url.host.as_str()].join(""));
+    }
+
+    let mut authority = ldap_url.host.to_string();
+    if let Some(port) = ldap_url.port {
+        authority = [authority.as_str(), ":", port.to_string().as_str()].join("");
+    }
+
+    let attributes : Vec<String> = ldap_url.attributes
+        .iter()
+        .map(|attribute| percent_encode(attribute, is_list_item_char))
+        .collect();
+
+    let extensions : Vec<String> = ldap_url.extensions
+        .iter()
+        .map(|extension| {
+            let mut built_extension = percent_encode(extension.name.as_str(), is_list_item_char);
+            if extension.critical {
+                built_extension = ["!", built_extension.as_str()].join("");
+            }
+            if let Some(value) = &extension.value {
+                let value = percent_encode(value, is_list_item_char);
+                built_extension = [built_extension.as_str(), "=", value.as_str()].join("");
+            }
+            built_extension
+        })
+        .collect();
+
+    let mut components = vec![
+        percent_encode(ldap_url.dn.as_str(), is_path_char),
+        attributes.join(","),
+        ldap_url.scope.map(|scope| scope.as_str().to_string()).unwrap_or_default(),
+        ldap_url.filter.as_ref().map(|filter| percent_encode(filter, is_path_char)).unwrap_or_default(),
+        extensions.join(","),
+    ];
+    while components.len() > 1 && components.last().unwrap().is_empty() {
+        components.pop();
+    }
+
+    Ok([ldap_url.scheme.as_str(), "://", authority.as_str(), "/", components.join("?").as_str()].join(""))
+}
+
+fn is_list_item_char(byte: u8) -> bool {
+    is_pchar(byte) && byte != b','
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_ldap_url, parse_ldap_url, LdapExtension, LdapScope, LdapUrl};
+
+    #[test]
+    fn parse_ldap_rfc_examples() {
+        let ldap_url = parse_ldap_url("ldap:///o=University%20of%20Michigan,c=US").unwrap();
+        assert_eq!(LdapUrl::new("", "o=University of Michigan,c=US"), ldap_url);
+        assert_eq!(LdapScope::Base, ldap_url.scope_or_default());
+        assert_eq!("(objectClass=*)", ldap_url.filter_or_default());
+
+        let ldap_url = parse_ldap_url("ldap://ldap1.example.net/o=University%20of%20Michigan,c=US?postalAddress").unwrap();
+        assert_eq!(vec!["postalAddress"], ldap_url.attributes);
+
+        let ldap_url = parse_ldap_url("ldap://ldap2.example.com/o=Question%3f,c=US?mail").unwrap();
+        assert_eq!("o=Question?,c=US", ldap_url.dn);
+
+        let ldap_url = parse_ldap_url("ldap:///??sub??e-bindname=cn=Manager%2cdc=example%2cdc=com,!x-foo").unwrap();
+        assert_eq!("", ldap_url.dn);
+        assert_eq!(Some(LdapScope::Sub), ldap_url.scope);
+        assert_eq!(vec![
+            LdapExtension { critical: false, name: "e-bindname".to_string(), value: Some("cn=Manager,dc=example,dc=com".to_string()) },
+            LdapExtension { critical: true, name: "x-foo".to_string(), value: None },
+        ], ldap_url.extensions);
+
+        let ldap_url = parse_ldap_url("LDAPS://host:636/dc=x?cn,mail?ONE?(%26(a=1)(b=2))").unwrap();
+        assert_eq!("LDAPS", ldap_url.scheme);
+        assert_eq!(Some(636), ldap_url.port);
+        assert_eq!(vec!["cn", "mail"], ldap_url.attributes);
+        assert_eq!(Some(LdapScope::One), ldap_url.scope);
+        assert_eq!("(&(a=1)(b=2))", ldap_url.filter.unwrap());
+    }
+
+    #[test]
+    fn parse_ldap_other_scheme() {
+        assert_eq!("error: not an LDAP URL: http://host/", parse_ldap_url("http://host/").err().unwrap());
+        assert_eq!("error: not an LDAP URL: ldap:o=x", parse_ldap_url("ldap:o=x").err().unwrap());
+    }
+
+    #[test]
+    fn parse_ldap_query_without_slash() {
+        let boxed_ldap_url = parse_ldap_url("ldap://host?cn");
+
+        assert!(boxed_ldap_url.is_err());
+        assert_eq!("error: LDAP URL must have '/' before query: ldap://host?cn", boxed_ldap_url.err().unwrap());
+    }
+
+    #[test]
+    fn parse_ldap_invalid_authority() {
+        assert_eq!("error: LDAP URL must not have user info: usr@host", parse_ldap_url("ldap://usr@host/").err().unwrap());
+        assert_eq!("error: LDAP URL host is not valid: a b", parse_ldap_url("ldap://a b/").err().unwrap());
+    }
+
+    #[test]
+    fn parse_ldap_fragment() {
+        let list = vec!["ldap://host/o=x#frag", "ldap://host/o=x??sub?(cn=a)#frag", "ldap://host/#"];
+
+        for url in list {
+            assert_eq!(["error: LDAP URL must not have fragment: ", url].join(""), parse_ldap_url(url).err().unwrap());
+        }
+    }
+
+    #[test]
+    fn build_ldap_invalid_host() {
+        let list = vec!["a/b", "a?b", "a#b", "a@b", "[::1"];
+
+        for host in list {
+            let boxed_url = build_ldap_url(&LdapUrl::new(host, "o=x"));
+            assert_eq!(["error: LDAP URL host is not valid: ", host].join(""), boxed_url.err().unwrap(), "host: {}", host);
+        }
+    }
+
+    #[test]
+    fn parse_ldap_invalid_scope() {
+        let boxed_ldap_url = parse_ldap_url("ldap://host/o=x?cn?tree");
+
+        assert!(boxed_ldap_url.is_err());
+        assert_eq!("error: LDAP URL scope is not one of base, one, sub: tree", boxed_ldap_url.err().unwrap());
+    }
+
+    #[test]
+    fn parse_ldap_too_many_components() {
+        let boxed_ldap_url = parse_ldap_url("ldap://host/o=x????e=1?x");
+
+        assert!(boxed_ldap_url.is_err());
+        assert_eq!("error: LDAP URL has more than 4 '?' separated components: ldap://host/o=x????e=1?x", boxed_ldap_url.err().unwrap());
+    }
+
+    #[test]
+    fn parse_ldap_extension_without_type() {
+        let boxed_ldap_url = parse_ldap_url("ldap://host/o=x????!=1");
+
+        assert!(boxed_ldap_url.is_err());
+        assert_eq!("error: LDAP URL extension has no type: !=1", boxed_ldap_url.err().unwrap());
+    }
+
+    #[test]
+    fn ldap_round_trip_empty_host() {
+        let url = "ldap:///o=University%20of%20Michigan,c=US";
+
+        assert_eq!(url, build_ldap_url(&parse_ldap_url(url).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn ldap_round_trip_port_scope_and_filter() {
+        let url = "ldap://ldap1.example.net:6666/o=University%20of%20Michigan,c=US??sub?(cn=Babs%20Jensen)";
+
+        assert_eq!(url, build_ldap_url(&parse_ldap_url(url).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn ldap_round_trip_ip_literal_host() {
+        let url = "ldap://[2001:db8::7]/c=GB?objectClass?one";
+
+        assert_eq!(url, build_ldap_url(&parse_ldap_url(url).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn ldap_round_trip_escaped_filter() {
+        let url = "ldap://ldap3.example.com/o=Babsco,c=US???(four-octet=%5C00%5C00%5C00%5C04)";
+
+        assert_eq!(url, build_ldap_url(&parse_ldap_url(url).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn ldap_round_trip_escaped_dn() {
+        let url = "ldap://ldap.example.com/o=An%20Example%5C2C%20Inc.,c=US";
+
+        assert_eq!(url, build_ldap_url(&parse_ldap_url(url).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn ldapi_round_trip_socket_path() {
+        let url = "ldapi://%2Fvar%2Frun%2Fslapd.sock/";
+
+        assert_eq!(url, build_ldap_url(&parse_ldap_url(url).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn ldap_round_trip_extensions() {
+        let url = "ldap:///??sub??!bindname=cn=Manager%2Co=Foo,x=a%3Fb";
+
+        assert_eq!(url, build_ldap_url(&parse_ldap_url(url).unwrap()).unwrap());
+    }
+}
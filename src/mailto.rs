@@ -0,0 +1,282 @@
+use crate::percent_encoding::{is_unreserved, percent_decode_to_string, percent_encode};
+
+/// Decoded `mailto:` URI, see [RFC 6068](https://www.rfc-editor.org/rfc/rfc6068)
+///
+/// - `to`: recipients from the path and from `to` header fields
+/// - `cc`, `bcc`: recipients from `cc` and `bcc` header fields
+/// - `subject`, `body`: first `subject` and `body` header fields, repeated ones are dropped
+/// - `headers`: any other header fields in order, names are kept as written
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct MailtoUrl {
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub bcc: Vec<String>,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    pub headers: Vec<(String, String)>
+}
+
+impl MailtoUrl {
+    /// Creates mailto URI with given recipients and no header fields
+    pub fn new(to: &[&str]) -> MailtoUrl {
+        MailtoUrl {
+            to: to.iter().map(|address| address.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns value of the first header field with given name, name is compared ignoring case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parses `mailto:` URI, addresses and header fields are percent-decoded as UTF-8
+///
+/// Addresses are separated by `,`, empty ones are skipped. Header field names `to`, `cc`, `bcc`,
+/// `subject` and `body` are recognized ignoring case, `+` is not treated as space. Fragment is ignored.
+/// Only the first `subject` and `body` are kept, repeated ones are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::parse_mailto_url;
+///
+/// let mailto_url = parse_mailto_url("mailto:a@example.com,b@example.com?cc=c@example.com&subject=Caf%C3%A9%20menu&X-Tag=1").unwrap();
+/// assert_eq!(vec!["a@example.com", "b@example.com"], mailto_url.to);
+/// assert_eq!(vec!["c@example.com"], mailto_url.cc);
+/// assert_eq!(Some("Café menu"), mailto_url.subject.as_deref());
+/// assert_eq!("1", mailto_url.header("x-tag").unwrap());
+/// ```
+pub fn parse_mailto_url(url: &str) -> Result<MailtoUrl, String> {
+    let url = url.split_once('#').map(|(url, _)| url).unwrap_or(url);
+
+    let boxed_scheme = url.split_once(':');
+    if boxed_scheme.is_none() || !boxed_scheme.unwrap().0.eq_ignore_ascii_case("mailto") {
+        return Err(["error: not a mailto URI: ", url].join(""));
+    }
+    let remaining_url = boxed_scheme.unwrap().1;
+
+    let (to, hfields) = remaining_url.split_once('?').unwrap_or((remaining_url, ""));
+
+    let mut mailto_url = MailtoUrl { to: parse_addresses(to), ..Default::default() };
+
+    for hfield in hfields.split('&').filter(|hfield| !hfield.is_empty()) {
+        let (name, value) = hfield.split_once('=').unwrap_or((hfield, ""));
+        let name = percent_decode_to_string(name);
+        if name.is_empty() {
+            return Err(["error: mailto header field has no name: ", hfield].join(""));
+        }
+
+        match name.to_ascii_lowercase().as_str() {
+            "to" => mailto_url.to.append(&mut parse_addresses(value)),
+            "cc" => mailto_url.cc.append(&mut parse_addresses(value)),
+            "bcc" => mailto_url.bcc.append(&mut parse_addresses(value)),
+            "subject" => {
+                if mailto_url.subject.is_none() {
+                    mailto_url.subject = Some(percent_decode_to_string(value));
+                }
+            },
+            "body" => {
+                if mailto_url.body.is_none() {
+                    mailto_url.body = Some(percent_decode_to_string(value));
+                }
+            },
+            _ => mailto_url.headers.push((name, percent_decode_to_string(value))),
+        }
+    }
+
+    Ok(mailto_url)
+}
+
+/// Builds `mailto:` URI, recipients go to the path, other fields follow as header fields
+///
+/// Everything except unreserved characters and ``!$'()*+;:@`` is percent-encoded,
+/// `,` is kept only between addresses. Line breaks in body are written as `%0D%0A`.
+/// Entries in `headers` named `to`, `cc`, `bcc`, `subject` or `body` (any case) are skipped,
+/// these fields are written only from their own struct fields.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_mailto_url, parse_mailto_url, MailtoUrl};
+///
+/// let mailto_url = MailtoUrl {
+///     cc: vec!["boss@example.com".to_string()],
+///     subject: Some("Q&A: 100%".to_string()),
+///     body: Some("Hi,\nsee you".to_string()),
+///     ..MailtoUrl::new(&["a@example.com", "b@example.com"])
+/// };
+///
+/// let url = build_mailto_url(&mailto_url);
+/// assert_eq!("mailto:a@example.com,b@example.com?cc=boss@example.com&subject=Q%26A:%20100%25&body=Hi,%0D%0Asee%20you", url);
+/// assert_eq!("Hi,\r\nsee you", parse_mailto_url(url.as_str()).unwrap().body.unwrap());
+/// ```
+pub fn build_mailto_url(mailto_url: &MailtoUrl) -> String {
+    let mut hfields : Vec<String> = vec![];
+    if !mailto_url.cc.is_empty() {
+        hfields.push(["cc=", build_addresses(&mailto_url.cc).as_str()].join(""));
+    }
+    if !mailto_url.bcc.is_empty() {
+        hfields.push(["bcc=", build_addresses(&mailto_url.bcc).as_str()].join(""));
+    }
+    if let Some(subject) = &mailto_url.subject {
+        let subject = percent_encode(subject, is_qchar);
+        hfields.push(["subject=", subject.as_str()].join(""));
+    }
+    if let Some(body) = &mailto_url.body {
+        let body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+        hfields.push(["body=", percent_encode(body.as_str(), is_qchar).as_str()].join(""));
+    }
+    for (name, value) in mailto_url.headers.iter().filter(|(name, _)| !is_known_hfield_name(name)) {
+        hfields.push([percent_encode(name, is_qchar).as_str(), "=", percent_encode(value, is_qchar).as_str()].join(""));
+    }
+
+    let mut url = ["mailto:", build_addresses(&mailto_url.to).as_str()].join("");
+    if !hfields.is_empty() {
+        url = [url.as_str(), "?", hfields.join("&").as_str()].join("");
+    }
+    url
+}
+
+fn is_known_hfield_name(name: &str) -> bool {
+    matches!(name.to_ascii_lowercase().as_str(), "to" | "cc" | "bcc" | "subject" | "body")
+}
+
+fn is_qchar(byte: u8) -> bool {
+    is_unreserved(byte) || matches!(byte, b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b':' | b'@')
+}
+
+fn is_address_char(byte: u8) -> bool {
+    is_qchar(byte) && byte != b','
+}
+
+fn parse_addresses(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .filter(|address| !address.is_empty())
+        .map(percent_decode_to_string)
+        .collect()
+}
+
+fn build_addresses(addresses: &[String]) -> String {
+    let encoded : Vec<String> = addresses
+        .iter()
+        .map(|address| percent_encode(address, is_address_char))
+        .collect();
+    encoded.join(",")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_mailto_url, parse_mailto_url, MailtoUrl};
+
+    #[test]
+    fn parse_mailto_rfc_examples() {
+        let mailto_url = parse_mailto_url("mailto:chris@example.com").unwrap();
+        assert_eq!(MailtoUrl::new(&["chris@example.com"]), mailto_url);
+
+        let mailto_url = parse_mailto_url("mailto:infobot@example.com?subject=current-issue").unwrap();
+        assert_eq!("current-issue", mailto_url.subject.unwrap());
+
+        let mailto_url = parse_mailto_url("mailto:?to=joe@example.com&cc=bob@example.com&body=hello").unwrap();
+        assert_eq!(vec!["joe@example.com"], mailto_url.to);
+        assert_eq!(vec!["bob@example.com"], mailto_url.cc);
+        assert_eq!("hello", mailto_url.body.unwrap());
+
+        let mailto_url = parse_mailto_url("mailto:list@example.org?In-Reply-To=%3C3469A91.D10AF4C@example.com%3E").unwrap();
+        assert_eq!(vec![("In-Reply-To".to_string(), "<3469A91.D10AF4C@example.com>".to_string())], mailto_url.headers);
+
+        let mailto_url = parse_mailto_url("MAILTO:%22not%40me%22@example.org,user@%E3%83%89%E3%83%A1%E3%82%A4%E3%83%B3.example?BCC=a@x,,b@x&subject=a+b#frag").unwrap();
+        assert_eq!(vec!["\"not@me\"@example.org", "user@ドメイン.example"], mailto_url.to);
+        assert_eq!(vec!["a@x", "b@x"], mailto_url.bcc);
+        assert_eq!("a+b", mailto_url.subject.unwrap());
+    }
+
+    #[test]
+    fn parse_mailto_other_scheme() {
+        let boxed_mailto_url = parse_mailto_url("tel:+1-816-555-1212");
+
+        assert!(boxed_mailto_url.is_err());
+        assert_eq!("error: not a mailto URI: tel:+1-816-555-1212", boxed_mailto_url.err().unwrap());
+    }
+
+    #[test]
+    fn parse_mailto_header_field_without_name() {
+        let boxed_mailto_url = parse_mailto_url("mailto:a@x?=x");
+
+        assert!(boxed_mailto_url.is_err());
+        assert_eq!("error: mailto header field has no name: =x", boxed_mailto_url.err().unwrap());
+    }
+
+    #[test]
+    fn build_mailto_escapes_delimiters() {
+        let mailto_url = MailtoUrl {
+            to: vec!["\"a,b\"@example.com".to_string(), "c@example.com".to_string()],
+            bcc: vec!["d@example.com".to_string()],
+            body: Some("x=1&y=2?#".to_string()),
+            headers: vec![("X-Ref".to_string(), "a b".to_string())],
+            ..Default::default()
+        };
+
+        let url = build_mailto_url(&mailto_url);
+        assert_eq!("mailto:%22a%2Cb%22@example.com,c@example.com?bcc=d@example.com&body=x%3D1%26y%3D2%3F%23&X-Ref=a%20b", url);
+        assert_eq!(mailto_url, parse_mailto_url(url.as_str()).unwrap());
+
+        assert_eq!("mailto:", build_mailto_url(&MailtoUrl::default()));
+    }
+
+    #[test]
+    fn parse_mailto_keeps_first_subject_and_body() {
+        let mailto_url = parse_mailto_url("mailto:a@x?subject=one&Subject=two&body=first&BODY=second").unwrap();
+        assert_eq!("one", mailto_url.subject.unwrap());
+        assert_eq!("first", mailto_url.body.unwrap());
+        assert!(mailto_url.headers.is_empty());
+    }
+
+    #[test]
+    fn build_mailto_skips_known_names_in_headers() {
+        let mailto_url = MailtoUrl {
+            subject: Some("Hi".to_string()),
+            headers: vec![
+                ("Cc".to_string(), "x@example.com".to_string()),
+                ("SUBJECT".to_string(), "other".to_string()),
+                ("body".to_string(), "text".to_string()),
+                ("X-Ref".to_string(), "1".to_string()),
+            ],
+            ..MailtoUrl::new(&["a@example.com"])
+        };
+
+        let url = build_mailto_url(&mailto_url);
+        assert_eq!("mailto:a@example.com?subject=Hi&X-Ref=1", url);
+        let parsed_mailto_url = parse_mailto_url(url.as_str()).unwrap();
+        assert!(parsed_mailto_url.cc.is_empty());
+        assert_eq!("Hi", parsed_mailto_url.subject.unwrap());
+        assert_eq!(vec![("X-Ref".to_string(), "1".to_string())], parsed_mailto_url.headers);
+    }
+
+    #[test]
+    fn mailto_round_trip_recipients() {
+        let url = "mailto:a@example.com,b@example.com?cc=c@example.com,d@example.com&subject=Hello%20there";
+
+        assert_eq!(url, build_mailto_url(&parse_mailto_url(url).unwrap()));
+    }
+
+    #[test]
+    fn mailto_round_trip_utf8_subject_and_crlf_body() {
+        let url = "mailto:?subject=Caf%C3%A9&body=line%201%0D%0Aline%202";
+
+        assert_eq!(url, build_mailto_url(&parse_mailto_url(url).unwrap()));
+    }
+
+    #[test]
+    fn mailto_round_trip_other_headers() {
+        let url = "mailto:joe@example.com?X-Mailer=custom&Keywords=a,b";
+
+        assert_eq!(url, build_mailto_url(&parse_mailto_url(url).unwrap()));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::path::split_path;
+use crate::percent_encoding::{is_pchar, percent_decode_to_string, percent_encode};
+use crate::UrlComponents;
+
+/// Path segment with matrix parameters, like `cars;color=red;year=2020`
+///
+/// Name, parameter keys and values are percent-decoded. Parameter without `=` has `None` value,
+/// so `;used` and `;used=` are kept apart.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MatrixSegment {
+    pub name: String,
+    pub params: Vec<(String, Option<String>)>
+}
+
+impl MatrixSegment {
+    pub fn new(name: &str) -> MatrixSegment {
+        MatrixSegment { name: name.to_string(), params: vec![] }
+    }
+
+    /// Returns value of the first parameter with given key, parameter without value gives empty string
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.get_optional(key).map(|value| value.unwrap_or(""))
+    }
+
+    /// Returns value of the first parameter with given key, `Some(None)` means parameter is defined without value
+    pub fn get_optional(&self, key: &str) -> Option<Option<&str>> {
+        self.params
+            .iter()
+            .find(|(param_key, _)| param_key == key)
+            .map(|(_, value)| value.as_deref())
+    }
+}
+
+/// Parses path into segments with matrix parameters, leading slash is ignored,
+/// trailing slash gives the last segment with empty name and no parameters
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{parse_matrix_path, MatrixSegment};
+///
+/// let segments = parse_matrix_path("/cars;color=red;used/model;v=2");
+///
+/// assert_eq!(segments, vec![
+///     MatrixSegment {
+///         name: "cars".to_string(),
+///         params: vec![("color".to_string(), Some("red".to_string())), ("used".to_string(), None)]
+///     },
+///     MatrixSegment { name: "model".to_string(), params: vec![("v".to_string(), Some("2".to_string()))] },
+/// ]);
+/// assert_eq!("red", segments[0].get("color").unwrap());
+/// assert_eq!(Some(None), segments[0].get_optional("used"));
+///
+/// assert_eq!(vec![MatrixSegment::new("cars"), MatrixSegment::new("")], parse_matrix_path("/cars/"));
+/// ```
+pub fn parse_matrix_path(path: &str) -> Vec<MatrixSegment> {
+    let mut segments: Vec<MatrixSegment> = split_path(path)
+        .iter()
+        .map(|segment| parse_matrix_segment(segment))
+        .collect();
+    if !segments.is_empty() && path.ends_with('/') {
+        segments.push(MatrixSegment::new(""));
+    }
+    segments
+}
+
+/// Builds absolute path from segments with matrix parameters, special characters are percent-encoded,
+/// parameter without value is written without `=`
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_matrix_path, MatrixSegment};
+///
+/// let mut cars = MatrixSegment::new("cars");
+/// cars.params.push(("color".to_string(), Some("red;blue".to_string())));
+/// cars.params.push(("used".to_string(), None));
+///
+/// assert_eq!("/cars;color=red%3Bblue;used/model", build_matrix_path(&[cars, MatrixSegment::new("model")]));
+/// ```
+pub fn build_matrix_path(segments: &[MatrixSegment]) -> String {
+    let segments: Vec<String> = segments
+        .iter()
+        .map(build_matrix_segment)
+        .collect();
+
+    ["/".to_string(), segments.join("/")].join("")
+}
+
+impl UrlComponents {
+    /// Returns path segments with parsed matrix parameters, see [parse_matrix_path]
+    pub fn matrix_segments(&self) -> Vec<MatrixSegment> {
+        parse_matrix_path(self.path.as_str())
+    }
+
+    /// Replaces path with given segments, see [build_matrix_path]. Rootless path stays rootless.
+    pub fn set_matrix_segments(&mut self, segments: &[MatrixSegment]) {
+        let path = build_matrix_path(segments);
+        if self.authority.is_none() && !self.path.starts_with('/') {
+            self.path = path.strip_prefix('/').unwrap().to_string();
+        } else {
+            self.path = path;
+        }
+    }
+}
+
+fn parse_matrix_segment(segment: &str) -> MatrixSegment {
+    let mut parts = segment.split(';');
+    let name = percent_decode_to_string(parts.next().unwrap());
+
+    let mut params : Vec<(String, Option<String>)> = vec![];
+    for param in parts {
+        if param.is_empty() {
+            continue;
+        }
+        let param = match param.split_once('=') {
+            Some((key, value)) => (percent_decode_to_string(key), Some(percent_decode_to_string(value))),
+            None => (percent_decode_to_string(param), None),
+        };
+        params.push(param);
+    }
+
+    MatrixSegment { name, params }
+}
+
+fn build_matrix_segment(segment: &MatrixSegment) -> String {
+    let mut parts : Vec<String> = vec![percent_encode(segment.name.as_str(), is_matrix_value_char)];
+    for (key, value) in segment.params.iter() {
+        let mut part = percent_encode(key.as_str(), is_matrix_key_char);
+        if let Some(value) = value {
+            part = [part, "=".to_string(), percent_encode(value.as_str(), is_matrix_value_char)].join("");
+        }
+        parts.push(part);
+    }
+
+    parts.join(";")
+}
+
+fn is_matrix_value_char(byte: u8) -> bool {
+    is_pchar(byte) && byte != b';'
+}
+
+fn is_matrix_key_char(byte: u8) -> bool {
+    is_matrix_value_char(byte) && byte != b'='
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_matrix_path, build_url, parse_matrix_path, parse_url, MatrixSegment};
+
+    #[test]
+    fn parse_matrix_path_plain_segments() {
+        let segments = parse_matrix_path("/a/b%20c");
+
+        assert_eq!(segments, vec![MatrixSegment::new("a"), MatrixSegment::new("b c")]);
+        assert!(parse_matrix_path("/").is_empty());
+    }
+
+    #[test]
+    fn parse_matrix_path_escaped_and_valueless_params() {
+        let segments = parse_matrix_path("/cars;color=red%3Bblue;;used;note=a=b");
+
+        assert_eq!(segments, vec![MatrixSegment {
+            name: "cars".to_string(),
+            params: vec![
+                ("color".to_string(), Some("red;blue".to_string())),
+                ("used".to_string(), None),
+                ("note".to_string(), Some("a=b".to_string())),
+            ]
+        }]);
+        assert_eq!("", segments[0].get("used").unwrap());
+        assert_eq!(None, segments[0].get("year"));
+    }
+
+    #[test]
+    fn build_matrix_path_round_trip() {
+        let segments = vec![
+            MatrixSegment {
+                name: "c;a/r s".to_string(),
+                params: vec![
+                    ("k=e;y".to_string(), Some("v=a;l/ue".to_string())),
+                    ("k=e;y".to_string(), Some("".to_string())),
+                    ("flag".to_string(), None),
+                ]
+            },
+            MatrixSegment::new("model"),
+        ];
+
+        let path = build_matrix_path(&segments);
+
+        assert_eq!("/c%3Ba%2Fr%20s;k%3De%3By=v=a%3Bl%2Fue;k%3De%3By=;flag/model", path);
+        assert_eq!(segments, parse_matrix_path(path.as_str()));
+    }
+
+    #[test]
+    fn matrix_path_round_trip_keeps_valueless_params_and_trailing_slash() {
+        let path = "/cars;used;color=/model;v=2/";
+
+        let segments = parse_matrix_path(path);
+
+        assert_eq!(MatrixSegment::new(""), segments[2]);
+        assert_eq!(path, build_matrix_path(&segments));
+    }
+
+    #[test]
+    fn url_components_matrix_segments() {
+        let mut url_components = parse_url("http://somehost/cars;color=red;year=2020/model;v=2?q=1").unwrap();
+
+        let mut segments = url_components.matrix_segments();
+        assert_eq!("2020", segments[0].get("year").unwrap());
+
+        segments[1].params.push(("trim".to_string(), Some("gt".to_string())));
+        url_components.set_matrix_segments(&segments);
+
+        assert_eq!("http://somehost/cars;color=red;year=2020/model;v=2;trim=gt?q=1", build_url(url_components).unwrap());
+    }
+
+    #[test]
+    fn url_components_matrix_segments_rootless() {
+        let mut url_components = parse_url("urn:isbn;v=1").unwrap();
+
+        let segments = url_components.matrix_segments();
+        url_components.set_matrix_segments(&segments);
+
+        assert_eq!("isbn;v=1", url_components.path);
+    }
+}
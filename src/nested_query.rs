@@ -0,0 +1,565 @@
+use crate::{UrlComponents, UrlQuery};
+
+/// Value of the nested query, like `filter[status]=open&filter[tags][]=a&filter[tags][]=b`
+///
+/// Map keeps the order in which keys appeared in the query.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum QueryValue {
+    String(String),
+    Array(Vec<QueryValue>),
+    Map(Vec<(String, QueryValue)>)
+}
+
+impl QueryValue {
+    /// Returns value for the given key, if this is a map
+    pub fn get(&self, key: &str) -> Option<&QueryValue> {
+        match self {
+            QueryValue::Map(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            QueryValue::String(value) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<QueryValue>> {
+        match self {
+            QueryValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+/// How nested keys are written
+///
+/// - `Brackets`: `filter[status]=open`
+/// - `Dots`: `filter.status=open`, brackets are recognized as well while parsing
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum NestedQueryNotation {
+    #[default]
+    Brackets,
+    Dots
+}
+
+/// Options for [parse_nested_query] and [build_nested_query]
+///
+/// - `max_depth`: number of nested levels below top-level key, deeper part of the key is kept as one literal key
+/// - `array_limit`: highest array index, greater indices are treated as map keys
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct NestedQueryOptions {
+    pub notation: NestedQueryNotation,
+    pub max_depth: usize,
+    pub array_limit: usize
+}
+
+impl Default for NestedQueryOptions {
+    fn default() -> Self {
+        NestedQueryOptions { notation: NestedQueryNotation::Brackets, max_depth: 5, array_limit: 20 }
+    }
+}
+
+/// Parses flat query pairs into nested structure, the result is always a map
+///
+/// Repeated key without brackets turns into an array. Key with unbalanced bracket, like `a[`,
+/// is kept as literal flat key. Conflicting definitions, like `a=1&a[b]=2`, are reported as error.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{parse_nested_query, parse_url, NestedQueryOptions, QueryValue};
+///
+/// let url_components = parse_url("https://somehost/issues?filter[status]=open&filter[tags][]=a&filter[tags][]=b").unwrap();
+/// let value = parse_nested_query(url_components.query.as_ref().unwrap(), &NestedQueryOptions::default()).unwrap();
+///
+/// let filter = value.get("filter").unwrap();
+/// assert_eq!("open", filter.get("status").unwrap().as_str().unwrap());
+/// assert_eq!(
+///     &vec![QueryValue::String("a".to_string()), QueryValue::String("b".to_string())],
+///     filter.get("tags").unwrap().as_array().unwrap()
+/// );
+/// ```
+pub fn parse_nested_query(query: &UrlQuery, options: &NestedQueryOptions) -> Result<QueryValue, String> {
+    let mut root = Node::Map(vec![]);
+
+    for (key, value) in query.iter() {
+        let segments = split_key(key, options);
+        let mut node = Node::Leaf(value.to_string());
+        for segment in segments.iter().skip(1).rev() {
+            node = wrap_node(segment, node, options);
+        }
+        let entry = Node::Map(vec![(segments[0].to_string(), node)]);
+
+        root = merge_nodes(root, entry, key)?;
+    }
+
+    Ok(root.into_query_value())
+}
+
+/// Builds flat query pairs from the nested structure, top-level value has to be a map
+///
+/// Arrays of strings are written with empty brackets (`tags[]=a`), other arrays with indices (`items[0][id]=1`).
+/// Empty arrays and maps are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_nested_query, build_query_params, NestedQueryNotation, NestedQueryOptions, QueryEncoding, QueryValue};
+///
+/// let value = QueryValue::Map(vec![
+///     ("filter".to_string(), QueryValue::Map(vec![
+///         ("status".to_string(), QueryValue::String("open".to_string())),
+///         ("tags".to_string(), QueryValue::Array(vec![QueryValue::String("a".to_string())])),
+///     ])),
+/// ]);
+///
+/// let query = build_nested_query(&value, &NestedQueryOptions::default()).unwrap();
+/// assert_eq!("filter%5Bstatus%5D=open&filter%5Btags%5D%5B%5D=a", build_query_params(&query, QueryEncoding::Rfc3986));
+///
+/// let options = NestedQueryOptions { notation: NestedQueryNotation::Dots, ..Default::default() };
+/// let query = build_nested_query(&value, &options).unwrap();
+/// assert_eq!("filter.status=open&filter.tags%5B%5D=a", build_query_params(&query, QueryEncoding::Rfc3986));
+/// ```
+pub fn build_nested_query(value: &QueryValue, options: &NestedQueryOptions) -> Result<UrlQuery, String> {
+    let entries = match value {
+        QueryValue::Map(entries) => entries,
+        _ => return Err("error: top-level nested query value has to be a map".to_string()),
+    };
+
+    let mut query = UrlQuery::new();
+    for (key, value) in entries {
+        let is_literal_key = matches!(value, QueryValue::String(_)) && split_key(key, options) == vec![key.to_string()];
+        if !is_literal_key {
+            validate_key(key, options)?;
+        }
+        flatten_value(key.to_string(), value, 0, options, &mut query)?;
+    }
+    Ok(query)
+}
+
+impl UrlComponents {
+    /// Returns query parsed into nested structure, see [parse_nested_query]
+    pub fn nested_query(&self, options: &NestedQueryOptions) -> Result<QueryValue, String> {
+        if self.query.is_none() {
+            return Ok(QueryValue::Map(vec![]));
+        }
+        parse_nested_query(self.query.as_ref().unwrap(), options)
+    }
+
+    /// Replaces query with the flattened nested structure, see [build_nested_query]
+    pub fn set_nested_query(&mut self, value: &QueryValue, options: &NestedQueryOptions) -> Result<(), String> {
+        let query = build_nested_query(value, options)?;
+        self.query = Some(query);
+        Ok(())
+    }
+}
+
+enum Node {
+    Leaf(String),
+    Array(Vec<(Option<usize>, Node)>),
+    Map(Vec<(String, Node)>)
+}
+
+impl Node {
+    fn into_query_value(self) -> QueryValue {
+        match self {
+            Node::Leaf(value) => QueryValue::String(value),
+            Node::Map(entries) => QueryValue::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, node)| (key, node.into_query_value()))
+                    .collect()
+            ),
+            Node::Array(mut entries) => {
+                if entries.iter().all(|(index, _)| index.is_some()) {
+                    entries.sort_by_key(|(index, _)| index.unwrap());
+                }
+                QueryValue::Array(entries.into_iter().map(|(_, node)| node.into_query_value()).collect())
+            }
+        }
+    }
+}
+
+fn split_key(original_key: &str, options: &NestedQueryOptions) -> Vec<String> {
+    let mut key = original_key.to_string();
+    if options.notation == NestedQueryNotation::Dots {
+        key = dots_to_brackets(key.as_str());
+    }
+
+    let boxed_bracket = key.find('[');
+    if boxed_bracket.is_none() || boxed_bracket.unwrap() == 0 {
+        return vec![key];
+    }
+
+    let (parent, mut remaining_key) = key.split_at(boxed_bracket.unwrap());
+    let mut segments = vec![parent.to_string()];
+    while remaining_key.starts_with('[') && segments.len() <= options.max_depth {
+        let boxed_closing_bracket = remaining_key.find(']');
+        if boxed_closing_bracket.is_none() {
+            return vec![original_key.to_string()];
+        }
+        let closing_bracket = boxed_closing_bracket.unwrap();
+        segments.push(remaining_key[1..closing_bracket].to_string());
+        remaining_key = &remaining_key[closing_bracket + 1..];
+    }
+
+    if !remaining_key.is_empty() {
+        segments.push(remaining_key.to_string());
+    }
+    segments
+}
+
+fn dots_to_brackets(key: &str) -> String {
+    let mut converted = String::with_capacity(key.len());
+    let mut is_inside_brackets = false;
+    let mut is_inside_dot_segment = false;
+    for char in key.chars() {
+        match char {
+            '[' => {
+                if is_inside_dot_segment {
+                    converted.push(']');
+                    is_inside_dot_segment = false;
+                }
+                is_inside_brackets = true;
+                converted.push(char);
+            }
+            ']' => {
+                is_inside_brackets = false;
+                converted.push(char);
+            }
+            '.' if !is_inside_brackets && !converted.is_empty() => {
+                if is_inside_dot_segment {
+                    converted.push(']');
+                }
+                converted.push('[');
+                is_inside_dot_segment = true;
+            }
+            _ => converted.push(char),
+        }
+    }
+    if is_inside_dot_segment {
+        converted.push(']');
+    }
+    converted
+}
+
+fn wrap_node(segment: &str, node: Node, options: &NestedQueryOptions) -> Node {
+    if segment.is_empty() {
+        return Node::Array(vec![(None, node)]);
+    }
+
+    let boxed_index = segment.parse::<usize>().ok().filter(|index| index.to_string() == segment);
+    if let Some(index) = boxed_index {
+        if index <= options.array_limit {
+            return Node::Array(vec![(Some(index), node)]);
+        }
+    }
+
+    Node::Map(vec![(segment.to_string(), node)])
+}
+
+fn merge_nodes(target: Node, source: Node, key: &str) -> Result<Node, String> {
+    match (target, source) {
+        (Node::Map(mut target_entries), Node::Map(source_entries)) => {
+            for (source_key, source_node) in source_entries {
+                let boxed_position = target_entries.iter().position(|(target_key, _)| *target_key == source_key);
+                if boxed_position.is_none() {
+                    target_entries.push((source_key, source_node));
+                    continue;
+                }
+                let position = boxed_position.unwrap();
+                let (target_key, target_node) = target_entries.remove(position);
+                let merged = merge_nodes(target_node, source_node, key)?;
+                target_entries.insert(position, (target_key, merged));
+            }
+            Ok(Node::Map(target_entries))
+        }
+        (Node::Array(mut target_entries), Node::Array(source_entries)) => {
+            for (source_index, source_node) in source_entries {
+                let boxed_position = target_entries
+                    .iter()
+                    .position(|(target_index, _)| source_index.is_some() && *target_index == source_index);
+                if boxed_position.is_none() {
+                    target_entries.push((source_index, source_node));
+                    continue;
+                }
+                let position = boxed_position.unwrap();
+                let (target_index, target_node) = target_entries.remove(position);
+                let merged = merge_nodes(target_node, source_node, key)?;
+                target_entries.insert(position, (target_index, merged));
+            }
+            Ok(Node::Array(target_entries))
+        }
+        (Node::Array(target_entries), Node::Map(source_entries)) => {
+            merge_nodes(array_to_map(target_entries), Node::Map(source_entries), key)
+        }
+        (Node::Map(target_entries), Node::Array(source_entries)) => {
+            merge_nodes(Node::Map(target_entries), array_to_map(source_entries), key)
+        }
+        (Node::Leaf(target_value), Node::Leaf(source_value)) => {
+            Ok(Node::Array(vec![(None, Node::Leaf(target_value)), (None, Node::Leaf(source_value))]))
+        }
+        (Node::Array(mut target_entries), Node::Leaf(source_value)) => {
+            target_entries.push((None, Node::Leaf(source_value)));
+            Ok(Node::Array(target_entries))
+        }
+        (Node::Leaf(target_value), Node::Array(source_entries)) => {
+            let mut entries = vec![(None, Node::Leaf(target_value))];
+            entries.extend(source_entries);
+            Ok(Node::Array(entries))
+        }
+        _ => Err(["error: query key conflicts with previously defined structure: ", key].join("")),
+    }
+}
+
+fn array_to_map(entries: Vec<(Option<usize>, Node)>) -> Node {
+    Node::Map(
+        entries
+            .into_iter()
+            .enumerate()
+            .map(|(position, (index, node))| (index.unwrap_or(position).to_string(), node))
+            .collect()
+    )
+}
+
+fn flatten_value(prefix: String, value: &QueryValue, depth: usize, options: &NestedQueryOptions, query: &mut UrlQuery) -> Result<(), String> {
+    match value {
+        QueryValue::String(value) => {
+            query.append(prefix.as_str(), value.as_str());
+        }
+        QueryValue::Array(values) => {
+            check_depth(prefix.as_str(), depth, options)?;
+            let is_flat = values.iter().all(|value| matches!(value, QueryValue::String(_)));
+            if !is_flat && values.len() > options.array_limit + 1 {
+                return Err(["error: array is longer than array limit allows: ", prefix.as_str()].join(""));
+            }
+            for (index, value) in values.iter().enumerate() {
+                let index = if is_flat { "".to_string() } else { index.to_string() };
+                let key = [prefix.as_str(), "[", index.as_str(), "]"].join("");
+                flatten_value(key, value, depth + 1, options, query)?;
+            }
+        }
+        QueryValue::Map(entries) => {
+            check_depth(prefix.as_str(), depth, options)?;
+            for (key, value) in entries {
+                validate_key(key, options)?;
+                let key = match options.notation {
+                    NestedQueryNotation::Brackets => [prefix.as_str(), "[", key.as_str(), "]"].join(""),
+                    NestedQueryNotation::Dots => [prefix.as_str(), ".", key.as_str()].join(""),
+                };
+                flatten_value(key, value, depth + 1, options, query)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_depth(prefix: &str, depth: usize, options: &NestedQueryOptions) -> Result<(), String> {
+    if depth >= options.max_depth {
+        return Err(["error: nested query is deeper than max depth allows: ", prefix].join(""));
+    }
+    Ok(())
+}
+
+fn validate_key(key: &str, options: &NestedQueryOptions) -> Result<(), String> {
+    let is_there_a_bracket = key.contains('[') || key.contains(']');
+    let is_there_a_dot = options.notation == NestedQueryNotation::Dots && key.contains('.');
+    if key.is_empty() || is_there_a_bracket || is_there_a_dot {
+        return Err(["error: nested query key can't be represented: ", key].join(""));
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_nested_query, build_url, parse_nested_query, parse_query_params, parse_url, NestedQueryNotation, NestedQueryOptions, QueryEncoding, QueryValue};
+
+    fn parse(query: &str, options: &NestedQueryOptions) -> Result<QueryValue, String> {
+        parse_nested_query(&parse_query_params(query, QueryEncoding::Rfc3986), options)
+    }
+
+    #[test]
+    fn parse_brackets() {
+        let value = parse("filter[status]=open&filter[tags][]=a&filter[tags][]=b&page=2", &NestedQueryOptions::default()).unwrap();
+
+        assert_eq!(value, QueryValue::Map(vec![
+            ("filter".to_string(), QueryValue::Map(vec![
+                ("status".to_string(), QueryValue::String("open".to_string())),
+                ("tags".to_string(), QueryValue::Array(vec![QueryValue::String("a".to_string()), QueryValue::String("b".to_string())])),
+            ])),
+            ("page".to_string(), QueryValue::String("2".to_string())),
+        ]));
+    }
+
+    #[test]
+    fn parse_indices_and_repeated_keys() {
+        let value = parse("a[1]=y&a[0]=x&b=1&b=2&c[0][id]=1&c[1][id]=2&c[0][name]=n", &NestedQueryOptions::default()).unwrap();
+
+        assert_eq!(value, QueryValue::Map(vec![
+            ("a".to_string(), QueryValue::Array(vec![QueryValue::String("x".to_string()), QueryValue::String("y".to_string())])),
+            ("b".to_string(), QueryValue::Array(vec![QueryValue::String("1".to_string()), QueryValue::String("2".to_string())])),
+            ("c".to_string(), QueryValue::Array(vec![
+                QueryValue::Map(vec![("id".to_string(), QueryValue::String("1".to_string())), ("name".to_string(), QueryValue::String("n".to_string()))]),
+                QueryValue::Map(vec![("id".to_string(), QueryValue::String("2".to_string()))]),
+            ])),
+        ]));
+    }
+
+    #[test]
+    fn parse_array_limit() {
+        let options = NestedQueryOptions { array_limit: 1, ..Default::default() };
+
+        let value = parse("a[0]=x&a[5]=y&b[01]=z", &options).unwrap();
+
+        assert_eq!(value, QueryValue::Map(vec![
+            ("a".to_string(), QueryValue::Map(vec![("0".to_string(), QueryValue::String("x".to_string())), ("5".to_string(), QueryValue::String("y".to_string()))])),
+            ("b".to_string(), QueryValue::Map(vec![("01".to_string(), QueryValue::String("z".to_string()))])),
+        ]));
+    }
+
+    #[test]
+    fn parse_max_depth() {
+        let options = NestedQueryOptions { max_depth: 2, ..Default::default() };
+
+        let value = parse("a[b][c][d][e]=1", &options).unwrap();
+
+        assert_eq!(value, QueryValue::Map(vec![
+            ("a".to_string(), QueryValue::Map(vec![("b".to_string(), QueryValue::Map(vec![("c".to_string(), QueryValue::Map(vec![("[d][e]".to_string(), QueryValue::String("1".to_string()))]))]))])),
+        ]));
+    }
+
+    #[test]
+    fn parse_dots() {
+        let options = NestedQueryOptions { notation: NestedQueryNotation::Dots, ..Default::default() };
+
+        let value = parse("filter.status=open&filter.tags[]=a&filter[owner].name=me", &options).unwrap();
+
+        assert_eq!(value, QueryValue::Map(vec![
+            ("filter".to_string(), QueryValue::Map(vec![
+                ("status".to_string(), QueryValue::String("open".to_string())),
+                ("tags".to_string(), QueryValue::Array(vec![QueryValue::String("a".to_string())])),
+                ("owner".to_string(), QueryValue::Map(vec![("name".to_string(), QueryValue::String("me".to_string()))])),
+            ])),
+        ]));
+    }
+
+    #[test]
+    fn parse_dots_as_literal_in_brackets_notation() {
+        let value = parse("filter.status=open", &NestedQueryOptions::default()).unwrap();
+
+        assert_eq!(value, QueryValue::Map(vec![("filter.status".to_string(), QueryValue::String("open".to_string()))]));
+    }
+
+    #[test]
+    fn parse_unbalanced_bracket_as_literal_key() {
+        let value = parse("a[=1&b[c][d=2", &NestedQueryOptions::default()).unwrap();
+
+        assert_eq!(value, QueryValue::Map(vec![
+            ("a[".to_string(), QueryValue::String("1".to_string())),
+            ("b[c][d".to_string(), QueryValue::String("2".to_string())),
+        ]));
+    }
+
+    #[test]
+    fn build_round_trip_unbalanced_bracket() {
+        let query = parse_query_params("a[=1&b=2", QueryEncoding::Rfc3986);
+
+        let value = parse_nested_query(&query, &NestedQueryOptions::default()).unwrap();
+        assert_eq!(query, build_nested_query(&value, &NestedQueryOptions::default()).unwrap());
+    }
+
+    #[test]
+    fn parse_conflict() {
+        let boxed_value = parse("a=1&a[b]=2", &NestedQueryOptions::default());
+
+        assert_eq!("error: query key conflicts with previously defined structure: a[b]", boxed_value.err().unwrap());
+    }
+
+    #[test]
+    fn build_round_trip_brackets() {
+        let value = QueryValue::Map(vec![
+            ("filter".to_string(), QueryValue::Map(vec![
+                ("status".to_string(), QueryValue::String("open".to_string())),
+                ("tags".to_string(), QueryValue::Array(vec![QueryValue::String("a".to_string()), QueryValue::String("b".to_string())])),
+            ])),
+            ("items".to_string(), QueryValue::Array(vec![
+                QueryValue::Map(vec![("id".to_string(), QueryValue::String("1".to_string()))]),
+                QueryValue::Map(vec![("id".to_string(), QueryValue::String("2".to_string()))]),
+            ])),
+            ("page".to_string(), QueryValue::String("2".to_string())),
+        ]);
+
+        let options = NestedQueryOptions { notation: NestedQueryNotation::Brackets, ..Default::default() };
+
+        let query = build_nested_query(&value, &options).unwrap();
+        assert_eq!(value, parse_nested_query(&query, &options).unwrap());
+    }
+
+    #[test]
+    fn build_round_trip_dots() {
+        let value = QueryValue::Map(vec![
+            ("filter".to_string(), QueryValue::Map(vec![
+                ("status".to_string(), QueryValue::String("open".to_string())),
+                ("tags".to_string(), QueryValue::Array(vec![QueryValue::String("a".to_string()), QueryValue::String("b".to_string())])),
+            ])),
+            ("items".to_string(), QueryValue::Array(vec![
+                QueryValue::Map(vec![("id".to_string(), QueryValue::String("1".to_string()))]),
+                QueryValue::Map(vec![("id".to_string(), QueryValue::String("2".to_string()))]),
+            ])),
+            ("page".to_string(), QueryValue::String("2".to_string())),
+        ]);
+
+        let options = NestedQueryOptions { notation: NestedQueryNotation::Dots, ..Default::default() };
+
+        let query = build_nested_query(&value, &options).unwrap();
+        assert_eq!(value, parse_nested_query(&query, &options).unwrap());
+    }
+
+    #[test]
+    fn build_max_depth() {
+        let options = NestedQueryOptions { max_depth: 1, ..Default::default() };
+        let value = QueryValue::Map(vec![("a".to_string(), QueryValue::Map(vec![("b".to_string(), QueryValue::Map(vec![("c".to_string(), QueryValue::String("1".to_string()))]))]))]);
+
+        assert_eq!("error: nested query is deeper than max depth allows: a[b]", build_nested_query(&value, &options).err().unwrap());
+    }
+
+    #[test]
+    fn build_array_limit() {
+        let options = NestedQueryOptions { array_limit: 0, ..Default::default() };
+        let value = QueryValue::Map(vec![("a".to_string(), QueryValue::Array(vec![QueryValue::Map(vec![]), QueryValue::Map(vec![])]))]);
+
+        assert_eq!("error: array is longer than array limit allows: a", build_nested_query(&value, &options).err().unwrap());
+    }
+
+    #[test]
+    fn build_key_with_brackets() {
+        let value = QueryValue::Map(vec![("a[b]".to_string(), QueryValue::String("1".to_string()))]);
+
+        assert_eq!("error: nested query key can't be represented: a[b]", build_nested_query(&value, &NestedQueryOptions::default()).err().unwrap());
+    }
+
+    #[test]
+    fn build_not_a_map() {
+        assert!(build_nested_query(&QueryValue::String("1".to_string()), &NestedQueryOptions::default()).is_err());
+    }
+
+    #[test]
+    fn url_components_nested_query() {
+        let mut url_components = parse_url("https://somehost/issues").unwrap();
+        let value = QueryValue::Map(vec![("filter".to_string(), QueryValue::Map(vec![("tags".to_string(), QueryValue::Array(vec![QueryValue::String("a".to_string()), QueryValue::String("b".to_string())]))]))]);
+
+        url_components.set_nested_query(&value, &NestedQueryOptions::default()).unwrap();
+        let url = build_url(url_components).unwrap();
+        assert_eq!("https://somehost/issues?filter%5Btags%5D%5B%5D=a&filter%5Btags%5D%5B%5D=b", url);
+
+        let url_components = parse_url(url.as_str()).unwrap();
+        assert_eq!(value, url_components.nested_query(&NestedQueryOptions::default()).unwrap());
+    }
+}
@@ -0,0 +1,367 @@
+use crate::percent_encoding::{is_unreserved, percent_encode};
+use crate::{UrlAuthority, UrlComponents};
+
+/// Cloud storage service addressed by the object store URL
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ObjectStoreProvider {
+    /// `s3://`, `s3a://`, `s3n://`
+    S3,
+    /// `gs://`
+    Gcs,
+    /// `az://`, `abfs://`, `abfss://`, `wasb://`, `wasbs://`
+    Azure
+}
+
+/// Object store URL, like `s3://bucket/key`, `gs://bucket/object` or `abfss://container@account.dfs.core.windows.net/path`
+///
+/// - `scheme`: scheme as written
+/// - `bucket`: S3 or GCS bucket, Azure container
+/// - `account_host`: Azure storage account host, like `account.dfs.core.windows.net`, `None` for `az://container`
+/// - `key`: object key or prefix, taken literally, without leading `/`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ObjectStoreUrl {
+    pub scheme: String,
+    pub bucket: String,
+    pub account_host: Option<String>,
+    pub key: String
+}
+
+impl ObjectStoreUrl {
+    /// Returns storage service given by scheme
+    pub fn provider(&self) -> Option<ObjectStoreProvider> {
+        match self.scheme.to_ascii_lowercase().as_str() {
+            "s3" | "s3a" | "s3n" => Some(ObjectStoreProvider::S3),
+            "gs" => Some(ObjectStoreProvider::Gcs),
+            "az" | "abfs" | "abfss" | "wasb" | "wasbs" => Some(ObjectStoreProvider::Azure),
+            _ => None,
+        }
+    }
+
+    /// Returns Azure storage account name, the first label of `account_host`
+    pub fn account(&self) -> Option<&str> {
+        let account_host = self.account_host.as_ref()?;
+        account_host.split('.').next()
+    }
+
+    /// Returns true if key is empty or ends with `/`, so URL points to a prefix rather than an object
+    pub fn is_prefix(&self) -> bool {
+        self.key.is_empty() || self.key.ends_with('/')
+    }
+
+    /// Converts into virtual-hosted HTTPS endpoint URL, key is percent-encoded except `/`
+    ///
+    /// - S3: `https://<bucket>.s3.amazonaws.com/<key>`
+    /// - GCS: `https://<bucket>.storage.googleapis.com/<key>`
+    /// - S3 and GCS bucket with `.`: path-style `https://s3.amazonaws.com/<bucket>/<key>` and
+    ///   `https://storage.googleapis.com/<bucket>/<key>`, as wildcard certificates do not cover dotted hosts
+    /// - Azure: `https://<account host>/<container>/<key>`, `az://` needs `account_host` to be set
+    ///
+    /// Key segments `.` and `..` are written as `%2E` and `%2E%2E`, so they are not removed as dot segments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{build_url, parse_object_store_url};
+    ///
+    /// let object_store_url = parse_object_store_url("s3://bucket/key/with spaces.parquet").unwrap();
+    /// let url_components = object_store_url.to_https_url().unwrap();
+    /// assert_eq!("https://bucket.s3.amazonaws.com/key/with%20spaces.parquet", build_url(url_components).unwrap());
+    ///
+    /// let object_store_url = parse_object_store_url("abfss://container@account.dfs.core.windows.net/path/f.csv").unwrap();
+    /// let url_components = object_store_url.to_https_url().unwrap();
+    /// assert_eq!("https://account.dfs.core.windows.net/container/path/f.csv", build_url(url_components).unwrap());
+    /// ```
+    pub fn to_https_url(&self) -> Result<UrlComponents, String> {
+        let boxed_provider = self.provider();
+        if boxed_provider.is_none() {
+            return Err(["error: object store scheme is not supported: ", self.scheme.as_str()].join(""));
+        }
+
+        let key = encode_key(self.key.as_str());
+        let is_path_style = self.bucket.contains('.');
+        let (host, path) = match boxed_provider.unwrap() {
+            ObjectStoreProvider::S3 if is_path_style => ("s3.amazonaws.com".to_string(), ["/", self.bucket.as_str(), "/", key.as_str()].join("")),
+            ObjectStoreProvider::S3 => ([self.bucket.as_str(), ".s3.amazonaws.com"].join(""), ["/", key.as_str()].join("")),
+            ObjectStoreProvider::Gcs if is_path_style => ("storage.googleapis.com".to_string(), ["/", self.bucket.as_str(), "/", key.as_str()].join("")),
+            ObjectStoreProvider::Gcs => ([self.bucket.as_str(), ".storage.googleapis.com"].join(""), ["/", key.as_str()].join("")),
+            ObjectStoreProvider::Azure => {
+                if self.account_host.is_none() {
+                    return Err(["error: Azure storage account is not known: ", build_object_store_url(self).as_str()].join(""));
+                }
+                (self.account_host.as_ref().unwrap().to_string(), ["/", self.bucket.as_str(), "/", key.as_str()].join(""))
+            },
+        };
+
+        Ok(UrlComponents {
+            scheme: "https".to_string(),
+            authority: Some(UrlAuthority { user_info: None, host, port: None }),
+            path,
+            ..Default::default()
+        })
+    }
+}
+
+/// Parses object store URL, everything after the first `/` following bucket is the key,
+/// `?`, `#` and spaces are part of the key and percent-encoding is not decoded
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{parse_object_store_url, ObjectStoreProvider};
+///
+/// let object_store_url = parse_object_store_url("s3://bucket/key/with spaces.parquet").unwrap();
+/// assert_eq!(Some(ObjectStoreProvider::S3), object_store_url.provider());
+/// assert_eq!("bucket", object_store_url.bucket);
+/// assert_eq!("key/with spaces.parquet", object_store_url.key);
+///
+/// let object_store_url = parse_object_store_url("abfss://container@account.dfs.core.windows.net/path/").unwrap();
+/// assert_eq!(Some(ObjectStoreProvider::Azure), object_store_url.provider());
+/// assert_eq!("container", object_store_url.bucket);
+/// assert_eq!("account", object_store_url.account().unwrap());
+/// assert!(object_store_url.is_prefix());
+/// ```
+pub fn parse_object_store_url(url: &str) -> Result<ObjectStoreUrl, String> {
+    let boxed_scheme = url.split_once("://");
+    if boxed_scheme.is_none() {
+        return Err(["error: not an object store URL: ", url].join(""));
+    }
+    let (scheme, remaining_url) = boxed_scheme.unwrap();
+    let (authority, key) = remaining_url.split_once('/').unwrap_or((remaining_url, ""));
+
+    let mut object_store_url = ObjectStoreUrl {
+        scheme: scheme.to_string(),
+        bucket: authority.to_string(),
+        account_host: None,
+        key: key.to_string()
+    };
+
+    let boxed_provider = object_store_url.provider();
+    if boxed_provider.is_none() {
+        return Err(["error: object store scheme is not supported: ", scheme].join(""));
+    }
+    let provider = boxed_provider.unwrap();
+
+    if provider == ObjectStoreProvider::Azure && authority.contains('@') {
+        let (container, account_host) = authority.split_once('@').unwrap();
+        let is_valid_account_host = account_host.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|char| char.is_ascii_alphanumeric() || char == '-')
+        });
+        if !is_valid_account_host {
+            return Err(["error: Azure storage account host is not valid: ", account_host].join(""));
+        }
+        object_store_url.bucket = container.to_string();
+        object_store_url.account_host = Some(account_host.to_string());
+    }
+
+    if !is_valid_bucket(provider, object_store_url.bucket.as_str()) {
+        return Err(["error: object store bucket name is not valid: ", object_store_url.bucket.as_str()].join(""));
+    }
+
+    Ok(object_store_url)
+}
+
+/// Builds object store URL, key is written as is
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_object_store_url, ObjectStoreUrl};
+///
+/// let object_store_url = ObjectStoreUrl {
+///     scheme: "gs".to_string(),
+///     bucket: "b".to_string(),
+///     account_host: None,
+///     key: "o".to_string()
+/// };
+/// assert_eq!("gs://b/o", build_object_store_url(&object_store_url));
+/// ```
+pub fn build_object_store_url(object_store_url: &ObjectStoreUrl) -> String {
+    let mut url = [object_store_url.scheme.as_str(), "://", object_store_url.bucket.as_str()].join("");
+    if let Some(account_host) = &object_store_url.account_host {
+        url = [url.as_str(), "@", account_host].join("");
+    }
+    if !object_store_url.key.is_empty() {
+        url = [url.as_str(), "/", object_store_url.key.as_str()].join("");
+    }
+    url
+}
+
+fn encode_key(key: &str) -> String {
+    let segments : Vec<String> = key.split('/')
+        .map(|segment| match segment {
+            "." => "%2E".to_string(),
+            ".." => "%2E%2E".to_string(),
+            _ => percent_encode(segment, is_key_char),
+        })
+        .collect();
+    segments.join("/")
+}
+
+fn is_key_char(byte: u8) -> bool {
+    is_unreserved(byte) || byte == b'/'
+}
+
+/// Checks characters allowed in S3 and GCS bucket and Azure container names: lowercase letters, digits, `-`,
+/// S3 and GCS also allow `.` and GCS allows `_`, name starts and ends with letter or digit.
+/// Minimal length is not checked, so short names used in tests and emulators are accepted.
+/// Azure special containers `$root`, `$web` and `$logs` are accepted.
+fn is_valid_bucket(provider: ObjectStoreProvider, bucket: &str) -> bool {
+    if provider == ObjectStoreProvider::Azure && matches!(bucket, "$root" | "$web" | "$logs") {
+        return true;
+    }
+
+    let max_length = if provider == ObjectStoreProvider::Gcs && bucket.contains('.') { 222 } else { 63 };
+    let is_allowed_char = |char: char| {
+        char.is_ascii_lowercase() || char.is_ascii_digit() || char == '-'
+            || (char == '.' && provider != ObjectStoreProvider::Azure)
+            || (char == '_' && provider == ObjectStoreProvider::Gcs)
+    };
+    let is_alphanumeric = |char: Option<char>| char.is_some_and(|char| char.is_ascii_lowercase() || char.is_ascii_digit());
+
+    (1..=max_length).contains(&bucket.len())
+        && bucket.chars().all(is_allowed_char)
+        && is_alphanumeric(bucket.chars().next())
+        && is_alphanumeric(bucket.chars().last())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_object_store_url, build_url, parse_object_store_url, ObjectStoreProvider};
+
+    #[test]
+    fn parse_object_store_url_key_with_query_and_fragment_characters() {
+        let object_store_url = parse_object_store_url("gs://my_bucket/dir/obj?#1").unwrap();
+
+        assert_eq!(Some(ObjectStoreProvider::Gcs), object_store_url.provider());
+        assert_eq!("dir/obj?#1", object_store_url.key);
+        assert!(!object_store_url.is_prefix());
+    }
+
+    #[test]
+    fn parse_object_store_url_bucket_only() {
+        let object_store_url = parse_object_store_url("s3a://logs.example.com").unwrap();
+
+        assert_eq!("logs.example.com", object_store_url.bucket);
+        assert_eq!("", object_store_url.key);
+        assert!(object_store_url.is_prefix());
+    }
+
+    #[test]
+    fn parse_object_store_url_azure_without_account() {
+        let object_store_url = parse_object_store_url("az://raw-data/2024/01/").unwrap();
+
+        assert_eq!("raw-data", object_store_url.bucket);
+        assert!(object_store_url.account().is_none());
+        assert_eq!("2024/01/", object_store_url.key);
+    }
+
+    #[test]
+    fn parse_object_store_url_azure_special_container() {
+        let object_store_url = parse_object_store_url("wasbs://$web@site.blob.core.windows.net/index.html").unwrap();
+
+        assert_eq!("$web", object_store_url.bucket);
+        assert_eq!("site", object_store_url.account().unwrap());
+    }
+
+    #[test]
+    fn parse_object_store_url_without_scheme() {
+        let boxed_object_store_url = parse_object_store_url("bucket/key");
+
+        assert!(boxed_object_store_url.is_err());
+        assert_eq!("error: not an object store URL: bucket/key", boxed_object_store_url.err().unwrap());
+    }
+
+    #[test]
+    fn parse_object_store_url_unsupported_scheme() {
+        let boxed_object_store_url = parse_object_store_url("https://bucket/key");
+
+        assert!(boxed_object_store_url.is_err());
+        assert_eq!("error: object store scheme is not supported: https", boxed_object_store_url.err().unwrap());
+    }
+
+    #[test]
+    fn parse_object_store_url_invalid_bucket() {
+        assert_eq!("error: object store bucket name is not valid: My_Bucket", parse_object_store_url("s3://My_Bucket/key").err().unwrap());
+        assert_eq!("error: object store bucket name is not valid: a_b", parse_object_store_url("s3://a_b/key").err().unwrap());
+        assert_eq!("error: object store bucket name is not valid: ", parse_object_store_url("s3:///key").err().unwrap());
+        assert_eq!("error: object store bucket name is not valid: -bucket", parse_object_store_url("gs://-bucket/key").err().unwrap());
+        assert_eq!(
+            "error: object store bucket name is not valid: c.1",
+            parse_object_store_url("abfss://c.1@account.dfs.core.windows.net/").err().unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_object_store_url_invalid_account_host() {
+        let boxed_object_store_url = parse_object_store_url("abfss://container@acc_ount.dfs.core.windows.net/");
+
+        assert!(boxed_object_store_url.is_err());
+        assert_eq!("error: Azure storage account host is not valid: acc_ount.dfs.core.windows.net", boxed_object_store_url.err().unwrap());
+    }
+
+    #[test]
+    fn object_store_https_url_encodes_key() {
+        let url_components = parse_object_store_url("s3://bucket/a b/c+d%e.txt").unwrap().to_https_url().unwrap();
+        assert_eq!("https://bucket.s3.amazonaws.com/a%20b/c%2Bd%25e.txt", build_url(url_components).unwrap());
+
+        let url_components = parse_object_store_url("gs://b/dir/?x#y").unwrap().to_https_url().unwrap();
+        assert_eq!("https://b.storage.googleapis.com/dir/%3Fx%23y", build_url(url_components).unwrap());
+    }
+
+    #[test]
+    fn object_store_https_url_encodes_dot_segments() {
+        let url_components = parse_object_store_url("s3://bucket/a/../b/./c..d/.e/..").unwrap().to_https_url().unwrap();
+
+        assert_eq!("https://bucket.s3.amazonaws.com/a/%2E%2E/b/%2E/c..d/.e/%2E%2E", build_url(url_components).unwrap());
+    }
+
+    #[test]
+    fn object_store_https_url_without_key() {
+        let url_components = parse_object_store_url("s3n://bucket").unwrap().to_https_url().unwrap();
+
+        assert_eq!("https://bucket.s3.amazonaws.com/", build_url(url_components).unwrap());
+    }
+
+    #[test]
+    fn object_store_https_url_azure() {
+        let url_components = parse_object_store_url("wasbs://container@account.blob.core.chinacloudapi.cn/f").unwrap().to_https_url().unwrap();
+        assert_eq!("https://account.blob.core.chinacloudapi.cn/container/f", build_url(url_components).unwrap());
+
+        let mut object_store_url = parse_object_store_url("az://container/f").unwrap();
+        assert_eq!("error: Azure storage account is not known: az://container/f", object_store_url.to_https_url().err().unwrap());
+        object_store_url.account_host = Some("account.blob.core.windows.net".to_string());
+        assert_eq!("https://account.blob.core.windows.net/container/f", build_url(object_store_url.to_https_url().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn object_store_https_url_dotted_bucket() {
+        let url_components = parse_object_store_url("s3a://logs.example.com/2024/a b.log").unwrap().to_https_url().unwrap();
+        assert_eq!("s3.amazonaws.com", url_components.authority.as_ref().unwrap().host);
+        assert_eq!("https://s3.amazonaws.com/logs.example.com/2024/a%20b.log", build_url(url_components).unwrap());
+
+        let url_components = parse_object_store_url("gs://www.example.com").unwrap().to_https_url().unwrap();
+        assert_eq!("https://storage.googleapis.com/www.example.com/", build_url(url_components).unwrap());
+    }
+
+    #[test]
+    fn object_store_url_round_trip_literal_key() {
+        let url = "s3://bucket/key/with spaces.parquet";
+
+        assert_eq!(url, build_object_store_url(&parse_object_store_url(url).unwrap()));
+    }
+
+    #[test]
+    fn object_store_url_round_trip_azure_account() {
+        let url = "abfss://container@account.dfs.core.windows.net/path/to/dir/";
+
+        assert_eq!(url, build_object_store_url(&parse_object_store_url(url).unwrap()));
+    }
+
+    #[test]
+    fn object_store_url_round_trip_without_key() {
+        let url = "az://container";
+
+        assert_eq!(url, build_object_store_url(&parse_object_store_url(url).unwrap()));
+    }
+}
@@ -0,0 +1,793 @@
+use crate::percent_encoding::{is_unreserved, percent_decode_to_string, percent_encode};
+use crate::{UrlComponents, UrlQuery};
+
+/// Parameter serialization style, see [OpenAPI style values](https://spec.openapis.org/oas/v3.1.0#style-values)
+///
+/// `Form`, `SpaceDelimited`, `PipeDelimited` and `DeepObject` apply to query parameters,
+/// `Simple`, `Label` and `Matrix` apply to path parameters.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ParameterStyle {
+    Form,
+    SpaceDelimited,
+    PipeDelimited,
+    DeepObject,
+    Simple,
+    Label,
+    Matrix
+}
+
+/// Value of the parameter, object keeps properties in order
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ParameterValue {
+    Primitive(String),
+    Array(Vec<String>),
+    Object(Vec<(String, String)>)
+}
+
+/// Expected shape of the parameter, needed to parse it back
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ParameterKind {
+    Primitive,
+    Array,
+    Object
+}
+
+/// Serializes query parameter into list of decoded keys and values
+///
+/// Delimiters can't be escaped inside non-exploded values, so elements containing delimiter are refused.
+/// Non-exploded array with single empty element would read back as empty array, so it is refused as well.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{serialize_query_parameter, ParameterStyle, ParameterValue};
+///
+/// let value = ParameterValue::Array(vec!["3".to_string(), "4".to_string()]);
+///
+/// let pairs = serialize_query_parameter("id", &value, ParameterStyle::Form, true).unwrap();
+/// assert_eq!(vec![("id".to_string(), "3".to_string()), ("id".to_string(), "4".to_string())], pairs);
+///
+/// let pairs = serialize_query_parameter("id", &value, ParameterStyle::PipeDelimited, false).unwrap();
+/// assert_eq!(vec![("id".to_string(), "3|4".to_string())], pairs);
+/// ```
+pub fn serialize_query_parameter(name: &str, value: &ParameterValue, style: ParameterStyle, explode: bool) -> Result<Vec<(String, String)>, String> {
+    let delimiter = query_delimiter(style)?;
+    let not_applicable = not_applicable_error(name, style, explode);
+
+    match value {
+        ParameterValue::Primitive(value) => {
+            if style != ParameterStyle::Form {
+                return Err(not_applicable);
+            }
+            Ok(vec![(name.to_string(), value.to_string())])
+        }
+        ParameterValue::Array(values) => {
+            if style == ParameterStyle::DeepObject {
+                return Err(not_applicable);
+            }
+            if explode {
+                return Ok(values.iter().map(|value| (name.to_string(), value.to_string())).collect());
+            }
+            check_single_empty_value(name, values)?;
+            let joined = join_values(name, values.iter().map(|value| value.as_str()).collect(), delimiter)?;
+            Ok(vec![(name.to_string(), joined)])
+        }
+        ParameterValue::Object(properties) => {
+            if style == ParameterStyle::DeepObject {
+                if !explode {
+                    return Err(not_applicable);
+                }
+                for (key, _) in properties {
+                    if key.contains('[') || key.contains(']') {
+                        return Err(["error: property name can't be represented in deepObject style: ", key].join(""));
+                    }
+                }
+                return Ok(properties.iter().map(|(key, value)| ([name, "[", key, "]"].join(""), value.to_string())).collect());
+            }
+            if explode {
+                if style != ParameterStyle::Form {
+                    return Err(not_applicable);
+                }
+                return Ok(properties.clone());
+            }
+            let joined = join_values(name, flatten_properties(properties), delimiter)?;
+            Ok(vec![(name.to_string(), joined)])
+        }
+    }
+}
+
+/// Parses query parameter of the given kind, returns `None` if parameter is not defined
+///
+/// Object in `Form` style with `explode` takes every pair of the query, as keys are property names.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{parse_query_parameter, parse_url, ParameterKind, ParameterStyle, ParameterValue};
+///
+/// let url_components = parse_url("https://somehost/users?color[R]=100&color[G]=200").unwrap();
+/// let query = url_components.query.as_ref().unwrap();
+///
+/// let value = parse_query_parameter(query, "color", ParameterKind::Object, ParameterStyle::DeepObject, true).unwrap();
+/// assert_eq!(Some(ParameterValue::Object(vec![
+///     ("R".to_string(), "100".to_string()),
+///     ("G".to_string(), "200".to_string())
+/// ])), value);
+/// ```
+pub fn parse_query_parameter(query: &UrlQuery, name: &str, kind: ParameterKind, style: ParameterStyle, explode: bool) -> Result<Option<ParameterValue>, String> {
+    let delimiter = query_delimiter(style)?;
+    let not_applicable = not_applicable_error(name, style, explode);
+
+    match kind {
+        ParameterKind::Primitive => {
+            if style != ParameterStyle::Form {
+                return Err(not_applicable);
+            }
+            Ok(query.get(name).map(|value| ParameterValue::Primitive(value.to_string())))
+        }
+        ParameterKind::Array => {
+            if style == ParameterStyle::DeepObject {
+                return Err(not_applicable);
+            }
+            let values = query.get_all(name);
+            if values.is_empty() {
+                return Ok(None);
+            }
+            if explode {
+                return Ok(Some(ParameterValue::Array(values.iter().map(|value| value.to_string()).collect())));
+            }
+            Ok(Some(ParameterValue::Array(split_values(values[0], delimiter))))
+        }
+        ParameterKind::Object => {
+            if style == ParameterStyle::DeepObject {
+                if !explode {
+                    return Err(not_applicable);
+                }
+                let prefix = [name, "["].join("");
+                let properties: Vec<(String, String)> = query
+                    .iter()
+                    .filter(|(key, _)| key.starts_with(prefix.as_str()) && key.ends_with(']'))
+                    .map(|(key, value)| (key[prefix.len()..key.len() - 1].to_string(), value.to_string()))
+                    .collect();
+                if properties.is_empty() {
+                    return Ok(None);
+                }
+                return Ok(Some(ParameterValue::Object(properties)));
+            }
+            if explode {
+                if style != ParameterStyle::Form {
+                    return Err(not_applicable);
+                }
+                if query.is_empty() {
+                    return Ok(None);
+                }
+                return Ok(Some(ParameterValue::Object(query.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect())));
+            }
+            let boxed_value = query.get(name);
+            if boxed_value.is_none() {
+                return Ok(None);
+            }
+            let properties = pair_properties(name, split_values(boxed_value.unwrap(), delimiter))?;
+            Ok(Some(ParameterValue::Object(properties)))
+        }
+    }
+}
+
+/// Serializes path parameter, the result is percent-encoded and can be placed into path as is
+///
+/// Array with single empty element is refused unless it is exploded `Matrix` (`;id`),
+/// in other styles it would read back as empty array.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{serialize_path_parameter, ParameterStyle, ParameterValue};
+///
+/// let value = ParameterValue::Object(vec![("R".to_string(), "100".to_string()), ("G".to_string(), "200".to_string())]);
+///
+/// assert_eq!("R,100,G,200", serialize_path_parameter("color", &value, ParameterStyle::Simple, false).unwrap());
+/// assert_eq!(".R=100.G=200", serialize_path_parameter("color", &value, ParameterStyle::Label, true).unwrap());
+/// assert_eq!(";R=100;G=200", serialize_path_parameter("color", &value, ParameterStyle::Matrix, true).unwrap());
+/// ```
+pub fn serialize_path_parameter(name: &str, value: &ParameterValue, style: ParameterStyle, explode: bool) -> Result<String, String> {
+    check_path_style(name, style, explode)?;
+    let name = encode_path_value(name);
+
+    let (values, is_key_value): (Vec<String>, bool) = match value {
+        ParameterValue::Primitive(value) => (vec![encode_path_value(value)], false),
+        ParameterValue::Array(values) => (values.iter().map(|value| encode_path_value(value)).collect(), false),
+        ParameterValue::Object(properties) => {
+            if explode {
+                let values = properties
+                    .iter()
+                    .map(|(key, value)| [encode_path_value(key), "=".to_string(), encode_path_value(value)].join(""))
+                    .collect();
+                (values, true)
+            } else {
+                (flatten_properties(properties).iter().map(|value| encode_path_value(value)).collect(), false)
+            }
+        }
+    };
+    let is_exploded_array = explode && matches!(value, ParameterValue::Array(_));
+    if let ParameterValue::Array(values) = value {
+        if !(is_exploded_array && style == ParameterStyle::Matrix) {
+            check_single_empty_value(name.as_str(), values)?;
+        }
+    }
+
+    let serialized = match style {
+        ParameterStyle::Label => {
+            let delimiter = if explode { "." } else { "," };
+            [".".to_string(), values.join(delimiter)].join("")
+        }
+        ParameterStyle::Matrix => {
+            if is_key_value {
+                values.iter().map(|value| [";", value.as_str()].join("")).collect::<Vec<String>>().join("")
+            } else if is_exploded_array {
+                values.iter().map(|value| matrix_pair(name.as_str(), value)).collect::<Vec<String>>().join("")
+            } else {
+                matrix_pair(name.as_str(), values.join(",").as_str())
+            }
+        }
+        _ => values.join(","),
+    };
+    Ok(serialized)
+}
+
+/// Parses percent-encoded path parameter of the given kind
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{parse_path_parameter, ParameterKind, ParameterStyle, ParameterValue};
+///
+/// let value = parse_path_parameter("id", ";id=3;id=4", ParameterKind::Array, ParameterStyle::Matrix, true).unwrap();
+/// assert_eq!(ParameterValue::Array(vec!["3".to_string(), "4".to_string()]), value);
+/// ```
+pub fn parse_path_parameter(name: &str, serialized: &str, kind: ParameterKind, style: ParameterStyle, explode: bool) -> Result<ParameterValue, String> {
+    check_path_style(name, style, explode)?;
+    let malformed = ["error: path parameter is malformed: ", name, " | ", serialized].join("");
+    if style == ParameterStyle::Matrix && explode && serialized.is_empty() {
+        match kind {
+            ParameterKind::Array => return Ok(ParameterValue::Array(vec![])),
+            ParameterKind::Object => return Ok(ParameterValue::Object(vec![])),
+            ParameterKind::Primitive => return Err(malformed),
+        }
+    }
+    let name = encode_path_value(name);
+
+    let raw_values: Vec<&str> = match style {
+        ParameterStyle::Label => {
+            let boxed_value = serialized.strip_prefix('.');
+            if boxed_value.is_none() {
+                return Err(malformed);
+            }
+            let delimiter = if explode && kind != ParameterKind::Primitive { '.' } else { ',' };
+            split_raw(boxed_value.unwrap(), delimiter)
+        }
+        ParameterStyle::Matrix => {
+            let boxed_value = serialized.strip_prefix(';');
+            if boxed_value.is_none() {
+                return Err(malformed);
+            }
+            let parts: Vec<&str> = boxed_value.unwrap().split(';').collect();
+            if explode && kind == ParameterKind::Object {
+                parts
+            } else {
+                let mut values: Vec<&str> = vec![];
+                for part in parts {
+                    let (part_name, value) = part.split_once('=').unwrap_or((part, ""));
+                    if part_name != name {
+                        return Err(malformed);
+                    }
+                    if explode {
+                        values.push(value);
+                    } else {
+                        values.append(&mut split_raw(value, ','));
+                    }
+                }
+                values
+            }
+        }
+        _ => split_raw(serialized, ','),
+    };
+
+    match kind {
+        ParameterKind::Primitive => {
+            if raw_values.len() > 1 {
+                return Err(malformed);
+            }
+            Ok(ParameterValue::Primitive(raw_values.first().map(|value| percent_decode_to_string(value)).unwrap_or_default()))
+        }
+        ParameterKind::Array => {
+            Ok(ParameterValue::Array(raw_values.iter().map(|value| percent_decode_to_string(value)).collect()))
+        }
+        ParameterKind::Object => {
+            if explode {
+                let mut properties: Vec<(String, String)> = vec![];
+                for raw_value in raw_values {
+                    let boxed_split = raw_value.split_once('=');
+                    if boxed_split.is_none() {
+                        return Err(malformed);
+                    }
+                    let (key, value) = boxed_split.unwrap();
+                    properties.push((percent_decode_to_string(key), percent_decode_to_string(value)));
+                }
+                return Ok(ParameterValue::Object(properties));
+            }
+            let values = raw_values.iter().map(|value| percent_decode_to_string(value)).collect();
+            Ok(ParameterValue::Object(pair_properties(name.as_str(), values)?))
+        }
+    }
+}
+
+/// Matches path against template like `/users/{id}/orders`, returns percent-encoded value for each placeholder
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::match_path_template;
+///
+/// let params = match_path_template("/users/{id}/orders/{order}", "/users/.5/orders/;order=7").unwrap();
+/// assert_eq!(vec![("id".to_string(), ".5".to_string()), ("order".to_string(), ";order=7".to_string())], params);
+///
+/// assert!(match_path_template("/users/{id}", "/groups/5").is_none());
+/// ```
+pub fn match_path_template(template: &str, path: &str) -> Option<Vec<(String, String)>> {
+    let mut params: Vec<(String, String)> = vec![];
+    let mut remaining_template = template;
+    let mut remaining_path = path;
+
+    loop {
+        let boxed_open = remaining_template.find('{');
+        if boxed_open.is_none() {
+            if remaining_template == remaining_path {
+                return Some(params);
+            }
+            return None;
+        }
+
+        let open = boxed_open.unwrap();
+        let literal = &remaining_template[..open];
+        remaining_path = remaining_path.strip_prefix(literal)?;
+
+        let close = remaining_template[open..].find('}')? + open;
+        let name = &remaining_template[open + 1..close];
+        remaining_template = &remaining_template[close + 1..];
+
+        let next_open = remaining_template.find('{').unwrap_or(remaining_template.len());
+        let next_literal = &remaining_template[..next_open];
+        let mut end = remaining_path.find('/').unwrap_or(remaining_path.len());
+        if !next_literal.is_empty() {
+            let boxed_end = remaining_path[..end].find(next_literal);
+            end = boxed_end.unwrap_or(end);
+        }
+
+        params.push((name.to_string(), remaining_path[..end].to_string()));
+        remaining_path = &remaining_path[end..];
+    }
+}
+
+impl UrlComponents {
+    /// Appends serialized query parameter to the query, see [serialize_query_parameter]
+    pub fn append_query_parameter(&mut self, name: &str, value: &ParameterValue, style: ParameterStyle, explode: bool) -> Result<(), String> {
+        let pairs = serialize_query_parameter(name, value, style, explode)?;
+
+        if self.query.is_none() {
+            self.query = Some(UrlQuery::new());
+        }
+        let query = self.query.as_mut().unwrap();
+        for (key, value) in pairs {
+            query.append(key.as_str(), value.as_str());
+        }
+        Ok(())
+    }
+
+    /// Returns parsed query parameter, see [parse_query_parameter]
+    pub fn query_parameter(&self, name: &str, kind: ParameterKind, style: ParameterStyle, explode: bool) -> Result<Option<ParameterValue>, String> {
+        if self.query.is_none() {
+            return Ok(None);
+        }
+        parse_query_parameter(self.query.as_ref().unwrap(), name, kind, style, explode)
+    }
+
+    /// Replaces `{name}` placeholder in the path with serialized path parameter, see [serialize_path_parameter]
+    pub fn set_path_parameter(&mut self, name: &str, value: &ParameterValue, style: ParameterStyle, explode: bool) -> Result<(), String> {
+        let placeholder = ["{", name, "}"].join("");
+        if !self.path.contains(placeholder.as_str()) {
+            return Err(["error: path does not contain placeholder ", placeholder.as_str()].join(""));
+        }
+
+        let serialized = serialize_path_parameter(name, value, style, explode)?;
+        self.path = self.path.replace(placeholder.as_str(), serialized.as_str());
+        Ok(())
+    }
+}
+
+fn query_delimiter(style: ParameterStyle) -> Result<char, String> {
+    match style {
+        ParameterStyle::Form | ParameterStyle::DeepObject => Ok(','),
+        ParameterStyle::SpaceDelimited => Ok(' '),
+        ParameterStyle::PipeDelimited => Ok('|'),
+        _ => Err(format!("error: style is not applicable to query parameters: {:?}", style)),
+    }
+}
+
+fn check_path_style(name: &str, style: ParameterStyle, explode: bool) -> Result<(), String> {
+    match style {
+        ParameterStyle::Simple | ParameterStyle::Label | ParameterStyle::Matrix => Ok(()),
+        _ => Err(not_applicable_error(name, style, explode)),
+    }
+}
+
+fn not_applicable_error(name: &str, style: ParameterStyle, explode: bool) -> String {
+    format!("error: style {:?} with explode={} is not applicable to parameter {}", style, explode, name)
+}
+
+fn flatten_properties(properties: &[(String, String)]) -> Vec<&str> {
+    properties
+        .iter()
+        .flat_map(|(key, value)| [key.as_str(), value.as_str()])
+        .collect()
+}
+
+fn join_values(name: &str, values: Vec<&str>, delimiter: char) -> Result<String, String> {
+    if values.iter().any(|value| value.contains(delimiter)) {
+        return Err(["error: value contains delimiter and can't be serialized: ", name].join(""));
+    }
+    Ok(values.join(delimiter.to_string().as_str()))
+}
+
+fn check_single_empty_value(name: &str, values: &[String]) -> Result<(), String> {
+    if values.len() == 1 && values[0].is_empty() {
+        return Err(["error: array with single empty element can't be told apart from empty array: ", name].join(""));
+    }
+    Ok(())
+}
+
+fn split_values(value: &str, delimiter: char) -> Vec<String> {
+    if value.is_empty() {
+        return vec![];
+    }
+    value.split(delimiter).map(|value| value.to_string()).collect()
+}
+
+fn split_raw(value: &str, delimiter: char) -> Vec<&str> {
+    if value.is_empty() {
+        return vec![];
+    }
+    value.split(delimiter).collect()
+}
+
+fn pair_properties(name: &str, values: Vec<String>) -> Result<Vec<(String, String)>, String> {
+    if values.len() % 2 != 0 {
+        return Err(["error: object parameter has key without value: ", name].join(""));
+    }
+    Ok(values.chunks(2).map(|pair| (pair[0].to_string(), pair[1].to_string())).collect())
+}
+
+fn matrix_pair(name: &str, value: &str) -> String {
+    if value.is_empty() {
+        return [";", name].join("");
+    }
+    [";", name, "=", value].join("")
+}
+
+fn encode_path_value(value: &str) -> String {
+    percent_encode(value, is_path_parameter_char)
+}
+
+fn is_path_parameter_char(byte: u8) -> bool {
+    is_unreserved(byte) && byte != b'.'
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_url, parse_path_parameter, parse_query_parameter, parse_url, serialize_path_parameter, serialize_query_parameter, match_path_template, ParameterKind, ParameterStyle, ParameterValue, UrlQuery};
+
+    fn query_round_trip(value: &ParameterValue, kind: ParameterKind, style: ParameterStyle, explode: bool) -> Option<ParameterValue> {
+        let mut url_components = parse_url("https://somehost/users").unwrap();
+        url_components.append_query_parameter("color", value, style, explode).unwrap();
+
+        let url = build_url(url_components).unwrap();
+        let url_components = parse_url(url.as_str()).unwrap();
+        url_components.query_parameter("color", kind, style, explode).unwrap()
+    }
+
+    fn path_round_trip(value: &ParameterValue, kind: ParameterKind, style: ParameterStyle, explode: bool) -> ParameterValue {
+        let mut url_components = parse_url("https://somehost/users/{color}/info").unwrap();
+        url_components.set_path_parameter("color", value, style, explode).unwrap();
+
+        let url = build_url(url_components).unwrap();
+        let url_components = parse_url(url.as_str()).unwrap();
+        let params = match_path_template("/users/{color}/info", url_components.path.as_str()).unwrap();
+        parse_path_parameter("color", params[0].1.as_str(), kind, style, explode).unwrap()
+    }
+
+    #[test]
+    fn query_form_primitive_round_trip() {
+        let value = ParameterValue::Primitive("blue sky".to_string());
+
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Primitive, ParameterStyle::Form, false));
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Primitive, ParameterStyle::Form, true));
+    }
+
+    #[test]
+    fn query_form_array_round_trip() {
+        let value = ParameterValue::Array(vec!["blue".to_string(), "black.1".to_string(), "brown=;".to_string()]);
+
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Array, ParameterStyle::Form, false));
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Array, ParameterStyle::Form, true));
+    }
+
+    #[test]
+    fn query_form_object_round_trip() {
+        let value = ParameterValue::Object(vec![
+            ("R".to_string(), "100".to_string()),
+            ("G".to_string(), "2.0".to_string()),
+            ("B".to_string(), "1=5".to_string()),
+        ]);
+
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Object, ParameterStyle::Form, false));
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Object, ParameterStyle::Form, true));
+    }
+
+    #[test]
+    fn query_space_delimited_array_round_trip() {
+        let value = ParameterValue::Array(vec!["blue".to_string(), "black.1".to_string(), "brown=;".to_string()]);
+
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Array, ParameterStyle::SpaceDelimited, false));
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Array, ParameterStyle::SpaceDelimited, true));
+    }
+
+    #[test]
+    fn query_space_delimited_object_round_trip() {
+        let value = ParameterValue::Object(vec![
+            ("R".to_string(), "100".to_string()),
+            ("G".to_string(), "2.0".to_string()),
+            ("B".to_string(), "1=5".to_string()),
+        ]);
+
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Object, ParameterStyle::SpaceDelimited, false));
+    }
+
+    #[test]
+    fn query_pipe_delimited_array_round_trip() {
+        let value = ParameterValue::Array(vec!["blue".to_string(), "black.1".to_string(), "brown=;".to_string()]);
+
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Array, ParameterStyle::PipeDelimited, false));
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Array, ParameterStyle::PipeDelimited, true));
+    }
+
+    #[test]
+    fn query_pipe_delimited_object_round_trip() {
+        let value = ParameterValue::Object(vec![
+            ("R".to_string(), "100".to_string()),
+            ("G".to_string(), "2.0".to_string()),
+            ("B".to_string(), "1=5".to_string()),
+        ]);
+
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Object, ParameterStyle::PipeDelimited, false));
+    }
+
+    #[test]
+    fn query_deep_object_round_trip() {
+        let value = ParameterValue::Object(vec![
+            ("R".to_string(), "100".to_string()),
+            ("G".to_string(), "2.0".to_string()),
+            ("B".to_string(), "1=5".to_string()),
+        ]);
+
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Object, ParameterStyle::DeepObject, true));
+    }
+
+    #[test]
+    fn query_styles_serialization() {
+        let array = ParameterValue::Array(vec!["blue".to_string(), "black".to_string()]);
+        let object = ParameterValue::Object(vec![("R".to_string(), "100".to_string()), ("G".to_string(), "200".to_string())]);
+
+        let list = vec![
+            (ParameterStyle::Form, false, &array, "color=blue%2Cblack"),
+            (ParameterStyle::Form, true, &array, "color=blue&color=black"),
+            (ParameterStyle::Form, false, &object, "color=R%2C100%2CG%2C200"),
+            (ParameterStyle::Form, true, &object, "R=100&G=200"),
+            (ParameterStyle::SpaceDelimited, false, &array, "color=blue%20black"),
+            (ParameterStyle::PipeDelimited, false, &object, "color=R%7C100%7CG%7C200"),
+            (ParameterStyle::DeepObject, true, &object, "color%5BR%5D=100&color%5BG%5D=200"),
+        ];
+
+        for (style, explode, value, expected) in list {
+            let mut url_components = parse_url("https://somehost/users").unwrap();
+            url_components.append_query_parameter("color", value, style, explode).unwrap();
+            let url = build_url(url_components).unwrap();
+            assert_eq!(["https://somehost/users?", expected].join(""), url, "{:?} explode={}", style, explode);
+        }
+    }
+
+    #[test]
+    fn query_styles_not_applicable() {
+        let primitive = ParameterValue::Primitive("blue".to_string());
+        let array = ParameterValue::Array(vec!["a,b".to_string()]);
+
+        assert!(serialize_query_parameter("color", &primitive, ParameterStyle::PipeDelimited, false).is_err());
+        assert!(serialize_query_parameter("color", &array, ParameterStyle::DeepObject, true).is_err());
+        assert!(serialize_query_parameter("color", &primitive, ParameterStyle::Simple, false).is_err());
+        assert_eq!(
+            "error: value contains delimiter and can't be serialized: color",
+            serialize_query_parameter("color", &array, ParameterStyle::Form, false).err().unwrap()
+        );
+
+        let query = UrlQuery::new();
+        assert_eq!(None, parse_query_parameter(&query, "color", ParameterKind::Array, ParameterStyle::Form, true).unwrap());
+    }
+
+    #[test]
+    fn path_simple_primitive_round_trip() {
+        let value = ParameterValue::Primitive("blue sky".to_string());
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Primitive, ParameterStyle::Simple, false));
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Primitive, ParameterStyle::Simple, true));
+    }
+
+    #[test]
+    fn path_simple_array_round_trip() {
+        let value = ParameterValue::Array(vec!["blue".to_string(), "black.1".to_string(), "brown=;".to_string()]);
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Array, ParameterStyle::Simple, false));
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Array, ParameterStyle::Simple, true));
+    }
+
+    #[test]
+    fn path_simple_object_round_trip() {
+        let value = ParameterValue::Object(vec![
+            ("R".to_string(), "100".to_string()),
+            ("G".to_string(), "2.0".to_string()),
+            ("B".to_string(), "1=5".to_string()),
+        ]);
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Object, ParameterStyle::Simple, false));
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Object, ParameterStyle::Simple, true));
+    }
+
+    #[test]
+    fn path_label_primitive_round_trip() {
+        let value = ParameterValue::Primitive("blue sky".to_string());
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Primitive, ParameterStyle::Label, false));
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Primitive, ParameterStyle::Label, true));
+    }
+
+    #[test]
+    fn path_label_array_round_trip() {
+        let value = ParameterValue::Array(vec!["blue".to_string(), "black.1".to_string(), "brown=;".to_string()]);
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Array, ParameterStyle::Label, false));
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Array, ParameterStyle::Label, true));
+    }
+
+    #[test]
+    fn path_label_object_round_trip() {
+        let value = ParameterValue::Object(vec![
+            ("R".to_string(), "100".to_string()),
+            ("G".to_string(), "2.0".to_string()),
+            ("B".to_string(), "1=5".to_string()),
+        ]);
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Object, ParameterStyle::Label, false));
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Object, ParameterStyle::Label, true));
+    }
+
+    #[test]
+    fn path_matrix_primitive_round_trip() {
+        let value = ParameterValue::Primitive("blue sky".to_string());
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Primitive, ParameterStyle::Matrix, false));
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Primitive, ParameterStyle::Matrix, true));
+    }
+
+    #[test]
+    fn path_matrix_array_round_trip() {
+        let value = ParameterValue::Array(vec!["blue".to_string(), "black.1".to_string(), "brown=;".to_string()]);
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Array, ParameterStyle::Matrix, false));
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Array, ParameterStyle::Matrix, true));
+    }
+
+    #[test]
+    fn path_matrix_object_round_trip() {
+        let value = ParameterValue::Object(vec![
+            ("R".to_string(), "100".to_string()),
+            ("G".to_string(), "2.0".to_string()),
+            ("B".to_string(), "1=5".to_string()),
+        ]);
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Object, ParameterStyle::Matrix, false));
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Object, ParameterStyle::Matrix, true));
+    }
+
+    #[test]
+    fn path_styles_serialization() {
+        let primitive = ParameterValue::Primitive("5".to_string());
+        let array = ParameterValue::Array(vec!["3".to_string(), "4".to_string()]);
+        let object = ParameterValue::Object(vec![("role".to_string(), "admin".to_string()), ("name".to_string(), "Alex".to_string())]);
+
+        let list = vec![
+            (ParameterStyle::Simple, false, &primitive, "5"),
+            (ParameterStyle::Simple, true, &array, "3,4"),
+            (ParameterStyle::Simple, true, &object, "role=admin,name=Alex"),
+            (ParameterStyle::Label, false, &primitive, ".5"),
+            (ParameterStyle::Label, false, &array, ".3,4"),
+            (ParameterStyle::Label, true, &array, ".3.4"),
+            (ParameterStyle::Label, false, &object, ".role,admin,name,Alex"),
+            (ParameterStyle::Matrix, false, &primitive, ";id=5"),
+            (ParameterStyle::Matrix, false, &array, ";id=3,4"),
+            (ParameterStyle::Matrix, true, &array, ";id=3;id=4"),
+            (ParameterStyle::Matrix, false, &object, ";id=role,admin,name,Alex"),
+            (ParameterStyle::Matrix, true, &object, ";role=admin;name=Alex"),
+        ];
+
+        for (style, explode, value, expected) in list {
+            assert_eq!(expected, serialize_path_parameter("id", value, style, explode).unwrap(), "{:?} explode={}", style, explode);
+        }
+
+        assert!(serialize_path_parameter("id", &primitive, ParameterStyle::Form, false).is_err());
+        assert!(parse_path_parameter("id", "5", ParameterKind::Primitive, ParameterStyle::Label, false).is_err());
+        assert!(parse_path_parameter("id", ";other=5", ParameterKind::Primitive, ParameterStyle::Matrix, false).is_err());
+    }
+
+    #[test]
+    fn path_simple_empty_array_round_trip() {
+        let value = ParameterValue::Array(vec![]);
+
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Array, ParameterStyle::Simple, false));
+    }
+
+    #[test]
+    fn path_matrix_exploded_empty_array_round_trip() {
+        let value = ParameterValue::Array(vec![]);
+
+        assert_eq!("", serialize_path_parameter("id", &value, ParameterStyle::Matrix, true).unwrap());
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Array, ParameterStyle::Matrix, true));
+    }
+
+    #[test]
+    fn path_matrix_exploded_single_empty_element_round_trip() {
+        let value = ParameterValue::Array(vec!["".to_string()]);
+
+        assert_eq!(";id", serialize_path_parameter("id", &value, ParameterStyle::Matrix, true).unwrap());
+        assert_eq!(value, path_round_trip(&value, ParameterKind::Array, ParameterStyle::Matrix, true));
+    }
+
+    #[test]
+    fn path_single_empty_element_not_supported() {
+        let value = ParameterValue::Array(vec!["".to_string()]);
+
+        assert_eq!(
+            "error: array with single empty element can't be told apart from empty array: id",
+            serialize_path_parameter("id", &value, ParameterStyle::Simple, false).err().unwrap()
+        );
+        assert!(serialize_path_parameter("id", &value, ParameterStyle::Label, true).is_err());
+        assert!(serialize_path_parameter("id", &value, ParameterStyle::Matrix, false).is_err());
+    }
+
+    #[test]
+    fn query_single_empty_element_not_supported() {
+        let value = ParameterValue::Array(vec!["".to_string()]);
+
+        assert_eq!(
+            "error: array with single empty element can't be told apart from empty array: color",
+            serialize_query_parameter("color", &value, ParameterStyle::Form, false).err().unwrap()
+        );
+        assert_eq!(Some(value.clone()), query_round_trip(&value, ParameterKind::Array, ParameterStyle::Form, true));
+    }
+
+    #[test]
+    fn set_path_parameter_without_placeholder() {
+        let mut url_components = parse_url("https://somehost/users").unwrap();
+        let value = ParameterValue::Primitive("5".to_string());
+
+        let boxed_result = url_components.set_path_parameter("id", &value, ParameterStyle::Simple, false);
+        assert_eq!("error: path does not contain placeholder {id}", boxed_result.err().unwrap());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::percent_encoding::{is_pchar, percent_decode_to_string, percent_encode};
+use crate::UrlComponents;
+
+impl UrlComponents {
+    /// Returns percent-decoded path segments, trailing slash does not produce an empty segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_url;
+    ///
+    /// let url_components = parse_url("https://somehost/a%2Fb/c%20d/").unwrap();
+    ///
+    /// assert_eq!(vec!["a/b", "c d"], url_components.path_segments());
+    /// assert!(url_components.has_trailing_slash());
+    /// ```
+    pub fn path_segments(&self) -> Vec<String> {
+        split_path(self.path.as_str())
+            .iter()
+            .map(|segment| percent_decode_to_string(segment))
+            .collect()
+    }
+
+    /// Returns true if path has at least one segment and ends with `/`.
+    pub fn has_trailing_slash(&self) -> bool {
+        !split_path(self.path.as_str()).is_empty() && self.path.ends_with('/')
+    }
+
+    /// Returns handle to change path segment by segment, pushed segments are percent-encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_url;
+    ///
+    /// let mut url_components = parse_url("https://somehost/api/").unwrap();
+    ///
+    /// url_components.path_segments_mut()
+    ///     .push("users")
+    ///     .extend(vec!["a/b", "c d"])
+    ///     .preserve_trailing_slash(false);
+    ///
+    /// assert_eq!("/api/users/a%2Fb/c%20d", url_components.path);
+    /// ```
+    pub fn path_segments_mut(&mut self) -> PathSegmentsMut<'_> {
+        let segments: Vec<String> = split_path(self.path.as_str())
+            .iter()
+            .map(|segment| segment.to_string())
+            .collect();
+        let trailing_slash = self.has_trailing_slash();
+        let absolute = self.authority.is_some() || self.path.starts_with('/');
+
+        PathSegmentsMut { url_components: self, segments, trailing_slash, absolute }
+    }
+}
+
+/// Mutable view on the path segments, every change is written back to the path immediately.
+///
+/// Untouched segments keep their original encoding.
+#[derive(Debug)]
+pub struct PathSegmentsMut<'a> {
+    url_components: &'a mut UrlComponents,
+    segments: Vec<String>,
+    trailing_slash: bool,
+    absolute: bool
+}
+
+impl<'a> PathSegmentsMut<'a> {
+    /// Appends segment, `/` within the segment is escaped so it stays one segment.
+    pub fn push(&mut self, segment: &str) -> &mut PathSegmentsMut<'a> {
+        self.segments.push(percent_encode(segment, is_pchar));
+        self.write();
+        self
+    }
+
+    /// Removes last segment and returns it percent-decoded.
+    pub fn pop(&mut self) -> Option<String> {
+        let boxed_segment = self.segments.pop();
+        self.write();
+        boxed_segment.map(|segment| percent_decode_to_string(segment.as_str()))
+    }
+
+    pub fn extend<I, S>(&mut self, segments: I) -> &mut PathSegmentsMut<'a>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for segment in segments {
+            self.segments.push(percent_encode(segment.as_ref(), is_pchar));
+        }
+        self.write();
+        self
+    }
+
+    /// Removes all segments, path becomes `/` if it was absolute.
+    pub fn clear(&mut self) -> &mut PathSegmentsMut<'a> {
+        self.segments.clear();
+        self.write();
+        self
+    }
+
+    /// Whether path ends with `/` after the last segment, by default existing trailing slash is preserved.
+    pub fn preserve_trailing_slash(&mut self, preserve: bool) -> &mut PathSegmentsMut<'a> {
+        self.trailing_slash = preserve;
+        self.write();
+        self
+    }
+
+    fn write(&mut self) {
+        if self.segments.is_empty() {
+            self.url_components.path = if self.absolute { "/".to_string() } else { "".to_string() };
+            return;
+        }
+
+        let mut path = self.segments.join("/");
+        if self.absolute {
+            path = ["/".to_string(), path].join("");
+        }
+        if self.trailing_slash {
+            path = [path, "/".to_string()].join("");
+        }
+        self.url_components.path = path;
+    }
+}
+
+pub(crate) fn split_path(path: &str) -> Vec<&str> {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    if path.is_empty() {
+        return vec![];
+    }
+
+    let mut segments: Vec<&str> = path.split('/').collect();
+    if path.ends_with('/') {
+        segments.pop();
+    }
+    segments
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_url;
+
+    #[test]
+    fn path_segments() {
+        let list = vec![
+            ("https://somehost", vec![], false),
+            ("https://somehost/", vec![], false),
+            ("https://somehost/a", vec!["a"], false),
+            ("https://somehost/a/", vec!["a"], true),
+            ("https://somehost/a//b%2Fc", vec!["a", "", "b/c"], false),
+            ("https://somehost/a//", vec!["a", ""], true),
+            ("urn:isbn:0451450523", vec!["isbn:0451450523"], false),
+        ];
+
+        for (url, segments, trailing_slash) in list {
+            let url_components = parse_url(url).unwrap();
+            assert_eq!(segments, url_components.path_segments(), "{}", url);
+            assert_eq!(trailing_slash, url_components.has_trailing_slash(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn path_segments_mut_push_pop() {
+        let mut url_components = parse_url("https://somehost").unwrap();
+
+        url_components.path_segments_mut().push("a/b").push("100%");
+        assert_eq!("/a%2Fb/100%25", url_components.path);
+        assert_eq!(vec!["a/b", "100%"], url_components.path_segments());
+
+        let segment = url_components.path_segments_mut().pop();
+        assert_eq!(Some("100%".to_string()), segment);
+        assert_eq!("/a%2Fb", url_components.path);
+    }
+
+    #[test]
+    fn path_segments_mut_keeps_untouched_segments() {
+        let mut url_components = parse_url("https://somehost/%7Euser/a%2fb").unwrap();
+
+        url_components.path_segments_mut().push("c");
+        assert_eq!("/%7Euser/a%2fb/c", url_components.path);
+    }
+
+    #[test]
+    fn path_segments_mut_trailing_slash() {
+        let mut url_components = parse_url("https://somehost/api/").unwrap();
+
+        url_components.path_segments_mut().push("users");
+        assert_eq!("/api/users/", url_components.path);
+
+        url_components.path_segments_mut().preserve_trailing_slash(false).pop();
+        assert_eq!("/api", url_components.path);
+
+        url_components.path_segments_mut().preserve_trailing_slash(true);
+        assert_eq!("/api/", url_components.path);
+
+        url_components.path_segments_mut().clear();
+        assert_eq!("/", url_components.path);
+    }
+
+    #[test]
+    fn path_segments_mut_rootless() {
+        let mut url_components = parse_url("mailto:").unwrap();
+
+        url_components.path_segments_mut().extend(["John.Doe@example.com"]);
+        assert_eq!("John.Doe@example.com", url_components.path);
+
+        url_components.path_segments_mut().clear();
+        assert_eq!("", url_components.path);
+    }
+}
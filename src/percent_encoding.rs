@@ -0,0 +1,157 @@
+//! Percent-encoding helpers shared by the builders and parsers.
+//!
+//! See [RFC 3986 section 2](https://www.rfc-editor.org/rfc/rfc3986#section-2) for more information.
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+pub(crate) fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'.' || byte == b'_' || byte == b'~'
+}
+
+pub(crate) fn is_sub_delim(byte: u8) -> bool {
+    matches!(byte, b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=')
+}
+
+pub(crate) fn is_userinfo_char(byte: u8) -> bool {
+    is_unreserved(byte) || is_sub_delim(byte) || byte == b':'
+}
+
+pub(crate) fn is_reg_name_char(byte: u8) -> bool {
+    is_unreserved(byte) || is_sub_delim(byte)
+}
+
+pub(crate) fn is_pchar(byte: u8) -> bool {
+    is_unreserved(byte) || is_sub_delim(byte) || byte == b':' || byte == b'@'
+}
+
+pub(crate) fn is_path_char(byte: u8) -> bool {
+    is_pchar(byte) || byte == b'/'
+}
+
+pub(crate) fn is_fragment_char(byte: u8) -> bool {
+    is_pchar(byte) || byte == b'/' || byte == b'?'
+}
+
+/// Percent-encodes every byte of the input which is not accepted by `keep`.
+pub(crate) fn percent_encode(input: &str, keep: fn(u8) -> bool) -> String {
+    percent_encode_bytes(input.as_bytes(), keep)
+}
+
+pub(crate) fn percent_encode_bytes(input: &[u8], keep: fn(u8) -> bool) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input {
+        if keep(*byte) {
+            encoded.push(*byte as char);
+        } else {
+            encoded.push('%');
+            encoded.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+            encoded.push(HEX_DIGITS[(byte & 0x0F) as usize] as char);
+        }
+    }
+    encoded
+}
+
+/// Same as [percent_encode], but keeps already existing `%XX` sequences as is.
+pub(crate) fn normalize_percent_encoding(input: &str, keep: fn(u8) -> bool) -> String {
+    let bytes = input.as_bytes();
+    let mut normalized = String::with_capacity(input.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' && is_percent_encoded_at(bytes, index) {
+            normalized.push_str(&input[index..index + 3]);
+            index += 3;
+            continue;
+        }
+
+        normalized.push_str(percent_encode_bytes(&bytes[index..index + 1], keep).as_str());
+        index += 1;
+    }
+    normalized
+}
+
+/// Decodes `%XX` sequences, malformed sequences are kept as is.
+pub(crate) fn percent_decode(input: &str) -> Vec<u8> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' && is_percent_encoded_at(bytes, index) {
+            decoded.push(hex_value(bytes[index + 1]) << 4 | hex_value(bytes[index + 2]));
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    decoded
+}
+
+/// Decodes `%XX` sequences into string, invalid UTF-8 is replaced with U+FFFD.
+pub(crate) fn percent_decode_to_string(input: &str) -> String {
+    String::from_utf8_lossy(percent_decode(input).as_slice()).to_string()
+}
+
+/// Returns true if every `%` in the input starts a valid `%XX` sequence
+/// and every other byte is accepted by `allowed`.
+pub(crate) fn is_valid_encoded(input: &str, allowed: fn(u8) -> bool) -> bool {
+    let bytes = input.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            if !is_percent_encoded_at(bytes, index) {
+                return false;
+            }
+            index += 3;
+            continue;
+        }
+
+        if !allowed(bytes[index]) {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+fn is_percent_encoded_at(bytes: &[u8], index: usize) -> bool {
+    index + 2 < bytes.len() && bytes[index + 1].is_ascii_hexdigit() && bytes[index + 2].is_ascii_hexdigit()
+}
+
+fn hex_value(byte: u8) -> u8 {
+    match byte {
+        b'0'..=b'9' => byte - b'0',
+        b'a'..=b'f' => byte - b'a' + 10,
+        _ => byte - b'A' + 10,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::percent_encoding::{is_pchar, is_valid_encoded, normalize_percent_encoding, percent_decode, percent_decode_to_string, percent_encode};
+
+    #[test]
+    fn encode_segment() {
+        assert_eq!("a%20b%2Fc%25", percent_encode("a b/c%", is_pchar));
+        assert_eq!("%D0%BF", percent_encode("п", is_pchar));
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!("a b/c%", percent_decode_to_string("a%20b%2fc%25"));
+        assert_eq!(vec![0xFF, b'%', b'4'], percent_decode("%ff%4"));
+    }
+
+    #[test]
+    fn normalize_keeps_existing_sequences() {
+        assert_eq!("a%20b%20%2F", normalize_percent_encoding("a b%20%2F", is_pchar));
+        assert_eq!("100%25", normalize_percent_encoding("100%", is_pchar));
+    }
+
+    #[test]
+    fn validate_encoded() {
+        assert!(is_valid_encoded("a%20b", is_pchar));
+        assert!(!is_valid_encoded("a b", is_pchar));
+        assert!(!is_valid_encoded("a%2", is_pchar));
+    }
+}
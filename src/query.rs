@@ -0,0 +1,695 @@
+use std::collections::HashMap;
+use crate::percent_encoding::{is_fragment_char, is_unreserved, is_valid_encoded, percent_decode_to_string, percent_encode};
+
+/// Rules used to encode and decode query keys and values
+///
+/// - `Rfc3986` treats `+` as literal plus sign, everything except unreserved characters is percent-encoded
+/// - `Form` follows [application/x-www-form-urlencoded](https://url.spec.whatwg.org/#application/x-www-form-urlencoded),
+///   `+` means space and space is serialized as `+`
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum QueryEncoding {
+    #[default]
+    Rfc3986,
+    Form
+}
+
+/// Options for [parse_query_params_with_options] and [build_query_params_with_options]
+///
+/// - `pair_delimiters`: characters separating pairs, building uses the first one
+/// - `key_value_separator`: character separating key from value
+///
+/// Delimiters are expected to be ASCII punctuation other than `%`, they are always percent-encoded inside keys and values.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct QueryOptions {
+    pub encoding: QueryEncoding,
+    pub pair_delimiters: Vec<char>,
+    pub key_value_separator: char
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        QueryOptions { encoding: QueryEncoding::Rfc3986, pair_delimiters: vec!['&'], key_value_separator: '=' }
+    }
+}
+
+impl From<QueryEncoding> for QueryOptions {
+    fn from(encoding: QueryEncoding) -> Self {
+        QueryOptions { encoding, ..Default::default() }
+    }
+}
+
+/// Ordered list of decoded query keys and values, the same key may be defined more than once
+///
+/// Key without value (`?debug`) is kept apart from key with empty value (`?debug=`),
+/// [UrlQuery::get] returns empty string for both, [UrlQuery::get_optional] tells them apart.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::UrlQuery;
+///
+/// let mut query = UrlQuery::new();
+/// query.append("tag", "a");
+/// query.append("tag", "b");
+/// query.insert("page", "1");
+///
+/// assert_eq!("a", query.get("tag").unwrap());
+/// assert_eq!(vec!["a", "b"], query.get_all("tag"));
+/// assert_eq!(3, query.len());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UrlQuery {
+    pairs: Vec<QueryPair>
+}
+
+/// Decoded key and value, raw encoded form is kept for pairs parsed from the URL
+#[derive(Clone, Debug)]
+struct QueryPair {
+    key: String,
+    value: Option<String>,
+    raw_key: Option<String>,
+    raw_value: Option<String>
+}
+
+impl QueryPair {
+    fn new(key: String, value: Option<String>) -> QueryPair {
+        QueryPair { key, value, raw_key: None, raw_value: None }
+    }
+}
+
+/// Only decoded keys and values are compared, raw encoded form is ignored
+impl PartialEq for UrlQuery {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter_optional().eq(other.iter_optional())
+    }
+}
+
+impl Eq for UrlQuery {}
+
+impl UrlQuery {
+    pub fn new() -> UrlQuery {
+        UrlQuery::default()
+    }
+
+    /// Returns value of the first pair with given key, key without value gives empty string
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.get_optional(key).map(|value| value.unwrap_or(""))
+    }
+
+    /// Returns value of the first pair with given key, `Some(None)` means key is defined without value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{parse_query_params, QueryEncoding};
+    ///
+    /// let params = parse_query_params("debug&verbose=", QueryEncoding::Rfc3986);
+    ///
+    /// assert_eq!(Some(None), params.get_optional("debug"));
+    /// assert_eq!(Some(Some("")), params.get_optional("verbose"));
+    /// assert_eq!(None, params.get_optional("trace"));
+    /// ```
+    pub fn get_optional(&self, key: &str) -> Option<Option<&str>> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.key == key)
+            .map(|pair| pair.value.as_deref())
+    }
+
+    /// Returns values of all pairs with given key, in order, key without value gives empty string
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        self.pairs
+            .iter()
+            .filter(|pair| pair.key == key)
+            .map(|pair| pair.value.as_deref().unwrap_or(""))
+            .collect()
+    }
+
+    /// Returns encoded value of the first pair with given key, as it was in the URL,
+    /// values set in code are encoded according to RFC 3986
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{parse_query_params, QueryEncoding};
+    ///
+    /// let mut params = parse_query_params("a=1%262&b=x+y", QueryEncoding::Form);
+    /// params.append("c", "3&4");
+    ///
+    /// assert_eq!("1&2", params.get("a").unwrap());
+    /// assert_eq!("1%262", params.get_raw("a").unwrap());
+    /// assert_eq!("x+y", params.get_raw("b").unwrap());
+    /// assert_eq!("3%264", params.get_raw("c").unwrap());
+    /// ```
+    pub fn get_raw(&self, key: &str) -> Option<String> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.key == key)
+            .map(|pair| raw_value(pair).unwrap_or_default())
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.pairs.iter().any(|pair| pair.key == key)
+    }
+
+    /// Sets value of the first pair with given key and removes the rest, appends pair if key is not defined
+    pub fn insert(&mut self, key: &str, value: &str) {
+        let boxed_index = self.pairs.iter().position(|pair| pair.key == key);
+        if boxed_index.is_none() {
+            self.append(key, value);
+            return;
+        }
+
+        let index = boxed_index.unwrap();
+        self.pairs[index].value = Some(value.to_string());
+        self.pairs[index].raw_value = None;
+
+        let mut position = 0;
+        self.pairs.retain(|pair| {
+            let keep = pair.key != key || position == index;
+            position += 1;
+            keep
+        });
+    }
+
+    /// Adds pair to the end of the list, existing pairs with the same key are kept
+    pub fn append(&mut self, key: &str, value: &str) {
+        self.pairs.push(QueryPair::new(key.to_string(), Some(value.to_string())));
+    }
+
+    /// Adds key without value to the end of the list, it is built as `key` instead of `key=`
+    pub fn append_key(&mut self, key: &str) {
+        self.pairs.push(QueryPair::new(key.to_string(), None));
+    }
+
+    /// Adds already percent-encoded pair, it is decoded once according to RFC 3986 and encoded form is kept
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{build_query_params, QueryEncoding, UrlQuery};
+    ///
+    /// let mut params = UrlQuery::new();
+    /// params.append_encoded("a", Some("1%262"));
+    ///
+    /// assert_eq!("1&2", params.get("a").unwrap());
+    /// assert_eq!("a=1%262", build_query_params(&params, QueryEncoding::Rfc3986));
+    /// ```
+    pub fn append_encoded(&mut self, key: &str, value: Option<&str>) {
+        self.pairs.push(QueryPair {
+            key: decode_query_component(key, QueryEncoding::Rfc3986),
+            value: value.map(|value| decode_query_component(value, QueryEncoding::Rfc3986)),
+            raw_key: Some(key.to_string()),
+            raw_value: value.map(|value| value.to_string())
+        });
+    }
+
+    /// Removes all pairs with given key, returns their values, key without value gives empty string
+    pub fn remove(&mut self, key: &str) -> Vec<String> {
+        let mut removed : Vec<String> = vec![];
+        self.pairs.retain(|pair| {
+            if pair.key == key {
+                removed.push(pair.value.clone().unwrap_or_default());
+                return false;
+            }
+            true
+        });
+        removed
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Iterates over pairs, key without value gives empty string
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|pair| (pair.key.as_str(), pair.value.as_deref().unwrap_or("")))
+    }
+
+    /// Iterates over pairs, key without value gives `None`
+    pub fn iter_optional(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.pairs.iter().map(|pair| (pair.key.as_str(), pair.value.as_deref()))
+    }
+
+    /// Returns encoded keys and values, see [UrlQuery::get_raw]
+    pub fn raw_pairs(&self) -> Vec<(String, Option<String>)> {
+        self.pairs
+            .iter()
+            .map(|pair| (raw_key(pair), raw_value(pair)))
+            .collect()
+    }
+}
+
+fn raw_key(pair: &QueryPair) -> String {
+    pair.raw_key.clone().unwrap_or_else(|| encode_query_component(pair.key.as_str(), QueryEncoding::Rfc3986))
+}
+
+fn raw_value(pair: &QueryPair) -> Option<String> {
+    if pair.raw_value.is_some() {
+        return pair.raw_value.clone();
+    }
+    pair.value.as_ref().map(|value| encode_query_component(value.as_str(), QueryEncoding::Rfc3986))
+}
+
+/// Pairs are sorted by key ignoring case, then by key, so the order of the HashMap does not leak into the URL
+impl From<HashMap<String, String>> for UrlQuery {
+    fn from(params: HashMap<String, String>) -> Self {
+        let mut pairs : Vec<(String, String)> = params.into_iter().collect();
+        pairs.sort_by(|(left, _), (right, _)| (left.to_lowercase(), left).cmp(&(right.to_lowercase(), right)));
+        UrlQuery::from(pairs)
+    }
+}
+
+impl From<Vec<(String, String)>> for UrlQuery {
+    fn from(pairs: Vec<(String, String)>) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+/// `None` value means key without value
+impl From<Vec<(String, Option<String>)>> for UrlQuery {
+    fn from(pairs: Vec<(String, Option<String>)>) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+impl FromIterator<(String, String)> for UrlQuery {
+    fn from_iter<T: IntoIterator<Item = (String, String)>>(iter: T) -> Self {
+        UrlQuery { pairs: iter.into_iter().map(|(key, value)| QueryPair::new(key, Some(value))).collect() }
+    }
+}
+
+/// `None` value means key without value
+impl FromIterator<(String, Option<String>)> for UrlQuery {
+    fn from_iter<T: IntoIterator<Item = (String, Option<String>)>>(iter: T) -> Self {
+        UrlQuery { pairs: iter.into_iter().map(|(key, value)| QueryPair::new(key, value)).collect() }
+    }
+}
+
+/// Key without value gives `None`
+impl IntoIterator for UrlQuery {
+    type Item = (String, Option<String>);
+    type IntoIter = std::vec::IntoIter<(String, Option<String>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let pairs : Vec<(String, Option<String>)> = self.pairs.into_iter().map(|pair| (pair.key, pair.value)).collect();
+        pairs.into_iter()
+    }
+}
+
+/// Parses query string (without leading `?`) into ordered list of decoded keys and values
+///
+/// Keys and values are decoded exactly once, encoded form is kept, see [UrlQuery::get_raw].
+/// Empty pairs are skipped, pair without `=` is kept as key without value, `=v` gives empty key.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{parse_query_params, QueryEncoding};
+///
+/// let params = parse_query_params("q=a+b%2B1", QueryEncoding::Form);
+/// assert_eq!("a b+1", params.get("q").unwrap());
+///
+/// let params = parse_query_params("q=a+b%2B1", QueryEncoding::Rfc3986);
+/// assert_eq!("a+b+1", params.get("q").unwrap());
+/// ```
+pub fn parse_query_params(query: &str, encoding: QueryEncoding) -> UrlQuery {
+    parse_query_params_with_options(query, &QueryOptions::from(encoding))
+}
+
+/// Same as [parse_query_params], pairs are split by the given delimiters and separator
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{parse_query_params_with_options, QueryOptions};
+///
+/// let options = QueryOptions { pair_delimiters: vec!['&', ';'], ..Default::default() };
+/// let params = parse_query_params_with_options("a=1;b=2&c=3", &options);
+/// assert_eq!(vec![("a", "1"), ("b", "2"), ("c", "3")], params.iter().collect::<Vec<(&str, &str)>>());
+///
+/// let options = QueryOptions { pair_delimiters: vec![';'], key_value_separator: ':', ..Default::default() };
+/// let params = parse_query_params_with_options("a:1=2;b:x%3Ay", &options);
+/// assert_eq!(vec![("a", "1=2"), ("b", "x:y")], params.iter().collect::<Vec<(&str, &str)>>());
+/// ```
+pub fn parse_query_params_with_options(query: &str, options: &QueryOptions) -> UrlQuery {
+    let mut params = UrlQuery::new();
+
+    for param in query.split(|char| options.pair_delimiters.contains(&char)) {
+        let boxed_split = param.split_once(options.key_value_separator);
+        let (raw_key, raw_value) = match boxed_split {
+            Some((key, value)) => (key, Some(value)),
+            None => (param, None),
+        };
+        if !param.is_empty() {
+            params.pairs.push(QueryPair {
+                key: decode_query_component(raw_key, options.encoding),
+                value: raw_value.map(|value| decode_query_component(value, options.encoding)),
+                raw_key: Some(raw_key.to_string()),
+                raw_value: raw_value.map(|value| value.to_string())
+            });
+        }
+    }
+
+    params
+}
+
+/// Builds query string (without leading `?`), order of the pairs is kept
+///
+/// Encoded form kept from parsing is reused if it decodes to the same key or value and contains
+/// only allowed characters, otherwise key or value is encoded again. So nothing is encoded twice and
+/// reserved characters never leak unescaped.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_query_params, QueryEncoding, UrlQuery};
+///
+/// let mut params = UrlQuery::new();
+/// params.append("q", "a b+1*");
+///
+/// assert_eq!("q=a+b%2B1*", build_query_params(&params, QueryEncoding::Form));
+/// assert_eq!("q=a%20b%2B1%2A", build_query_params(&params, QueryEncoding::Rfc3986));
+/// ```
+pub fn build_query_params(params: &UrlQuery, encoding: QueryEncoding) -> String {
+    build_query_params_with_options(params, &QueryOptions::from(encoding))
+}
+
+/// Same as [build_query_params], pairs are joined by the first of the given delimiters and separator
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_query_params_with_options, QueryOptions, UrlQuery};
+///
+/// let mut params = UrlQuery::new();
+/// params.append("a", "1|2");
+/// params.append("b", "3");
+///
+/// let options = QueryOptions { pair_delimiters: vec![';'], key_value_separator: '|', ..Default::default() };
+/// assert_eq!("a|1%7C2;b|3", build_query_params_with_options(&params, &options));
+/// ```
+pub fn build_query_params_with_options(params: &UrlQuery, options: &QueryOptions) -> String {
+    let pair_delimiter = options.pair_delimiters.first().copied().unwrap_or('&');
+    let separator = options.key_value_separator.to_string();
+
+    let key_value_list : Vec<String> = params.pairs
+        .iter()
+        .map(|pair| {
+            let key = encode_delimited_component(pair.key.as_str(), pair.raw_key.as_deref(), options);
+            if pair.value.is_none() {
+                return key;
+            }
+            let value = encode_delimited_component(pair.value.as_ref().unwrap(), pair.raw_value.as_deref(), options);
+            [key, separator.to_string(), value].join("")
+        })
+        .collect();
+
+    key_value_list.join(pair_delimiter.to_string().as_str())
+}
+
+fn encode_delimited_component(component: &str, raw: Option<&str>, options: &QueryOptions) -> String {
+    if let Some(raw) = raw.filter(|raw| is_reusable_raw(component, raw, options)) {
+        return raw.to_string();
+    }
+
+    let mut encoded = encode_query_component(component, options.encoding);
+    for delimiter in options.pair_delimiters.iter().chain([options.key_value_separator].iter()) {
+        if delimiter.is_ascii() && encoded.contains(*delimiter) {
+            encoded = encoded.replace(*delimiter, format!("%{:02X}", *delimiter as u8).as_str());
+        }
+    }
+    encoded
+}
+
+fn is_reusable_raw(component: &str, raw: &str, options: &QueryOptions) -> bool {
+    raw.is_ascii()
+        && is_valid_encoded(raw, is_fragment_char)
+        && !raw.contains(|char| char == options.key_value_separator || options.pair_delimiters.contains(&char))
+        && decode_query_component(raw, options.encoding) == component
+}
+
+pub(crate) fn encode_query_component(component: &str, encoding: QueryEncoding) -> String {
+    match encoding {
+        QueryEncoding::Rfc3986 => percent_encode(component, is_unreserved),
+        QueryEncoding::Form => percent_encode(component, is_form_char).replace("%20", "+"),
+    }
+}
+
+pub(crate) fn decode_query_component(component: &str, encoding: QueryEncoding) -> String {
+    match encoding {
+        QueryEncoding::Rfc3986 => percent_decode_to_string(component),
+        QueryEncoding::Form => percent_decode_to_string(component.replace('+', " ").as_str()),
+    }
+}
+
+fn is_form_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'*' || byte == b'-' || byte == b'.' || byte == b'_'
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::{build_query_params, build_query_params_with_options, parse_query_params, parse_query_params_with_options, QueryEncoding, QueryOptions, UrlQuery};
+
+    #[test]
+    fn parse_query_params_decodes() {
+        let params = parse_query_params("a=1%262&b=x%3Dy&c&=empty&d=e=f", QueryEncoding::Rfc3986);
+
+        assert_eq!(5, params.len());
+        assert_eq!("1&2", params.get("a").unwrap());
+        assert_eq!("x=y", params.get("b").unwrap());
+        assert_eq!("", params.get("c").unwrap());
+        assert_eq!("empty", params.get("").unwrap());
+        assert_eq!("e=f", params.get("d").unwrap());
+    }
+
+    #[test]
+    fn build_query_params_round_trip() {
+        let reserved = ":/?#[]@!$&'()*+,;=% ";
+        let mut params = UrlQuery::new();
+        for (index, char) in reserved.chars().enumerate() {
+            params.append(["key", char.to_string().as_str()].join("").as_str(), [index.to_string(), char.to_string()].join("").as_str());
+        }
+        params.append("%26", "%3D");
+        params.append("ключ", "значення");
+        params.append("ключ", "");
+
+        for encoding in [QueryEncoding::Rfc3986, QueryEncoding::Form] {
+            let query = build_query_params(&params, encoding);
+            assert_eq!(params, parse_query_params(query.as_str(), encoding));
+        }
+    }
+
+    #[test]
+    fn parse_query_params_form() {
+        let params = parse_query_params("name=John+Doe&name=Jane%2BDoe&&note=%E2%9C%93+ok", QueryEncoding::Form);
+
+        assert_eq!(vec!["John Doe", "Jane+Doe"], params.get_all("name"));
+        assert_eq!("✓ ok", params.get("note").unwrap());
+    }
+
+    #[test]
+    fn build_query_params_form() {
+        let params = UrlQuery::from(vec![
+            ("b".to_string(), "~a b+c".to_string()),
+            ("a".to_string(), "*-._".to_string()),
+        ]);
+
+        assert_eq!("b=%7Ea+b%2Bc&a=*-._", build_query_params(&params, QueryEncoding::Form));
+        assert_eq!("b=~a%20b%2Bc&a=%2A-._", build_query_params(&params, QueryEncoding::Rfc3986));
+    }
+
+    #[test]
+    fn query_params_custom_delimiters_round_trip() {
+        let params = UrlQuery::from(vec![
+            ("a;b".to_string(), "1&2".to_string()),
+            ("c".to_string(), "x:y|z~w".to_string()),
+            ("d".to_string(), "".to_string()),
+        ]);
+
+        let list = vec![
+            (vec![';'], ':', "a%3Bb:1%262;c:x%3Ay%7Cz~w;d:"),
+            (vec!['&', ';'], '|', "a%3Bb|1%262&c|x%3Ay%7Cz~w&d|"),
+            (vec!['&'], '~', "a%3Bb~1%262&c~x%3Ay%7Cz%7Ew&d~"),
+        ];
+
+        for (pair_delimiters, key_value_separator, expected) in list {
+            for encoding in [QueryEncoding::Rfc3986, QueryEncoding::Form] {
+                let options = QueryOptions { encoding, pair_delimiters: pair_delimiters.clone(), key_value_separator };
+                let query = build_query_params_with_options(&params, &options);
+                if encoding == QueryEncoding::Rfc3986 {
+                    assert_eq!(expected, query);
+                }
+                assert_eq!(params, parse_query_params_with_options(query.as_str(), &options));
+            }
+        }
+    }
+
+    #[test]
+    fn valueless_keys_round_trip() {
+        for query in ["debug", "debug=", "a&b=&c=1&a", "a=&a"] {
+            for encoding in [QueryEncoding::Rfc3986, QueryEncoding::Form] {
+                let params = parse_query_params(query, encoding);
+                assert_eq!(query, build_query_params(&params, encoding));
+            }
+        }
+
+        let params = parse_query_params("a&a=&a=1", QueryEncoding::Rfc3986);
+        assert_eq!(vec!["", "", "1"], params.get_all("a"));
+        assert_eq!(
+            vec![("a", None), ("a", Some("")), ("a", Some("1"))],
+            params.iter_optional().collect::<Vec<(&str, Option<&str>)>>()
+        );
+        assert_eq!(
+            vec![("a".to_string(), None), ("a".to_string(), Some("".to_string())), ("a".to_string(), Some("1".to_string()))],
+            params.into_iter().collect::<Vec<(String, Option<String>)>>()
+        );
+    }
+
+    #[test]
+    fn url_query_append_key() {
+        let mut params = UrlQuery::new();
+        params.append_key("debug");
+        params.append("verbose", "");
+
+        assert_eq!(Some(None), params.get_optional("debug"));
+        assert_eq!(Some(""), params.get("debug"));
+        assert_eq!("debug&verbose=", build_query_params(&params, QueryEncoding::Rfc3986));
+
+        params.insert("debug", "1");
+        assert_eq!(Some(Some("1")), params.get_optional("debug"));
+    }
+
+    #[test]
+    fn reserved_characters_are_encoded_once() {
+        let reserved = ":/?#[]@!$&'()*+,;=% ";
+
+        for char in reserved.chars() {
+            let text = ["a", char.to_string().as_str(), "b"].join("");
+            let mut params = UrlQuery::new();
+            params.append(text.as_str(), text.as_str());
+
+            for encoding in [QueryEncoding::Rfc3986, QueryEncoding::Form] {
+                let query = build_query_params(&params, encoding);
+                let (key, value) = query.split_once('=').unwrap();
+                assert!(!key.contains(['&', '=', '#', ' ']) && !value.contains(['&', '=', '#', ' ']), "{}", query);
+                assert!(!query.contains("%25") || char == '%', "{}", query);
+
+                let parsed = parse_query_params(query.as_str(), encoding);
+                assert_eq!(params, parsed);
+                assert_eq!(query, build_query_params(&parsed, encoding));
+            }
+        }
+    }
+
+    #[test]
+    fn raw_form_is_kept_and_reused() {
+        let reserved = ":/?#[]@!$&'()*+,;=% ";
+
+        for char in reserved.chars() {
+            let encoded = format!("%{:02X}", char as u8);
+            let lowercase = encoded.to_lowercase();
+            let query = ["k", encoded.as_str(), "=", lowercase.as_str(), "v"].join("");
+
+            let params = parse_query_params(query.as_str(), QueryEncoding::Rfc3986);
+            assert_eq!(["k", char.to_string().as_str()].join(""), params.iter().next().unwrap().0);
+            assert_eq!([char.to_string().as_str(), "v"].join(""), params.iter().next().unwrap().1);
+            assert_eq!(vec![(["k", encoded.as_str()].join(""), Some([lowercase.as_str(), "v"].join("")))], params.raw_pairs());
+
+            assert_eq!(query, build_query_params(&params, QueryEncoding::Rfc3986));
+        }
+    }
+
+    #[test]
+    fn raw_form_round_trip_keeps_pairs_apart() {
+        let params = parse_query_params("a=1%262&b=x%3Dy&c=%2526", QueryEncoding::Rfc3986);
+
+        assert_eq!(3, params.len());
+        assert_eq!("1&2", params.get("a").unwrap());
+        assert_eq!("x=y", params.get("b").unwrap());
+        assert_eq!("%26", params.get("c").unwrap());
+        assert_eq!("a=1%262&b=x%3Dy&c=%2526", build_query_params(&params, QueryEncoding::Rfc3986));
+
+        let mut params = UrlQuery::new();
+        params.append("c", "%26");
+        assert_eq!("c=%2526", build_query_params(&params, QueryEncoding::Rfc3986));
+    }
+
+    #[test]
+    fn raw_form_is_not_reused_when_unsafe() {
+        let mut params = parse_query_params("a=x;y&b=c+d&c=d e&d=ü", QueryEncoding::Rfc3986);
+
+        let options = QueryOptions { pair_delimiters: vec![';'], ..Default::default() };
+        assert_eq!("a=x%3By;b=c+d;c=d%20e;d=%C3%BC", build_query_params_with_options(&params, &options));
+
+        assert_eq!("a=x;y&b=c%2Bd&c=d+e&d=%C3%BC", build_query_params(&params, QueryEncoding::Form));
+
+        params.insert("a", "1&2");
+        assert_eq!("1%262", params.get_raw("a").unwrap());
+        assert_eq!("a=1%262&b=c+d&c=d%20e&d=%C3%BC", build_query_params(&params, QueryEncoding::Rfc3986));
+    }
+
+    #[test]
+    fn append_encoded_pair() {
+        let mut params = UrlQuery::new();
+        params.append_encoded("a%5B%5D", Some("x%2By"));
+        params.append_encoded("flag", None);
+
+        assert_eq!(vec![("a[]", Some("x+y")), ("flag", None)], params.iter_optional().collect::<Vec<(&str, Option<&str>)>>());
+        assert_eq!("a%5B%5D=x%2By&flag", build_query_params(&params, QueryEncoding::Rfc3986));
+        assert_eq!("a%5B%5D=x%2By&flag", build_query_params(&params, QueryEncoding::Form));
+    }
+
+    #[test]
+    fn parse_query_params_mixed_delimiters() {
+        let options = QueryOptions { pair_delimiters: vec!['&', ';'], ..Default::default() };
+        let params = parse_query_params_with_options("a=1;;b=2&&c;=empty", &options);
+
+        assert_eq!(vec![("a", "1"), ("b", "2"), ("c", ""), ("", "empty")], params.iter().collect::<Vec<(&str, &str)>>());
+    }
+
+    #[test]
+    fn url_query_insert_and_remove() {
+        let mut params = UrlQuery::from(vec![
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+            ("a".to_string(), "3".to_string()),
+        ]);
+
+        params.insert("a", "4");
+        assert_eq!(vec![("a", "4"), ("b", "2")], params.iter().collect::<Vec<(&str, &str)>>());
+
+        params.insert("c", "5");
+        params.append("c", "6");
+        assert_eq!(vec!["5".to_string(), "6".to_string()], params.remove("c"));
+        assert!(!params.contains_key("c"));
+    }
+
+    #[test]
+    fn url_query_from_hash_map_is_sorted() {
+        let mut map = HashMap::new();
+        map.insert("b".to_string(), "2".to_string());
+        map.insert("A".to_string(), "1".to_string());
+        map.insert("c".to_string(), "3".to_string());
+
+        let params = UrlQuery::from(map);
+        assert_eq!(vec![("A", "1"), ("b", "2"), ("c", "3")], params.iter().collect::<Vec<(&str, &str)>>());
+    }
+
+    #[test]
+    fn url_query_from_hash_map_keys_differing_in_case() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), "2".to_string());
+        map.insert("b".to_string(), "3".to_string());
+        map.insert("A".to_string(), "1".to_string());
+
+        let params = UrlQuery::from(map);
+        assert_eq!(vec![("A", "1"), ("a", "2"), ("b", "3")], params.iter().collect::<Vec<(&str, &str)>>());
+    }
+}
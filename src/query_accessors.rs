@@ -0,0 +1,219 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::UrlComponents;
+
+/// Problem found while reading typed query parameter
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum QueryParamError {
+    Missing(String),
+    Invalid { key: String, value: String, reason: String },
+}
+
+impl QueryParamError {
+    /// Name of the parameter that caused the error
+    pub fn key(&self) -> &str {
+        match self {
+            QueryParamError::Missing(key) => key.as_str(),
+            QueryParamError::Invalid { key, .. } => key.as_str(),
+        }
+    }
+}
+
+impl fmt::Display for QueryParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryParamError::Missing(key) =>
+                write!(f, "query parameter is missing: {}", key),
+            QueryParamError::Invalid { key, value, reason } =>
+                write!(f, "query parameter {} has invalid value '{}': {}", key, value, reason),
+        }
+    }
+}
+
+impl std::error::Error for QueryParamError {}
+
+impl UrlComponents {
+    /// Parses value of the first pair with given key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::{parse_url, QueryParamError};
+    ///
+    /// let url_components = parse_url("https://somehost/search?page=2&size=big").unwrap();
+    ///
+    /// assert_eq!(2, url_components.query_get::<u32>("page").unwrap());
+    /// assert_eq!(QueryParamError::Missing("offset".to_string()), url_components.query_get::<u32>("offset").err().unwrap());
+    /// assert_eq!(
+    ///     "query parameter size has invalid value 'big': invalid digit found in string",
+    ///     url_components.query_get::<u32>("size").err().unwrap().to_string()
+    /// );
+    /// ```
+    pub fn query_get<T: FromStr>(&self, key: &str) -> Result<T, QueryParamError> where T::Err: fmt::Display {
+        let boxed_value = self.query_value(key);
+        if boxed_value.is_none() {
+            return Err(QueryParamError::Missing(key.to_string()));
+        }
+        parse_value(key, boxed_value.unwrap())
+    }
+
+    /// Parses value of the first pair with given key, returns default if key is not defined
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_url;
+    ///
+    /// let url_components = parse_url("https://somehost/search?page=2").unwrap();
+    ///
+    /// assert_eq!(2, url_components.query_get_or("page", 1).unwrap());
+    /// assert_eq!(20, url_components.query_get_or("size", 20).unwrap());
+    /// ```
+    pub fn query_get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, QueryParamError> where T::Err: fmt::Display {
+        let boxed_value = self.query_value(key);
+        if boxed_value.is_none() {
+            return Ok(default);
+        }
+        parse_value(key, boxed_value.unwrap())
+    }
+
+    /// Reads boolean flag, key without value or with `true`, `1`, `yes`, `on` is true,
+    /// missing key or `false`, `0`, `no`, `off` is false, case is ignored
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_url;
+    ///
+    /// let url_components = parse_url("https://somehost/search?debug&verbose=Yes&cache=0").unwrap();
+    ///
+    /// assert!(url_components.query_flag("debug").unwrap());
+    /// assert!(url_components.query_flag("verbose").unwrap());
+    /// assert!(!url_components.query_flag("cache").unwrap());
+    /// assert!(!url_components.query_flag("trace").unwrap());
+    /// ```
+    pub fn query_flag(&self, key: &str) -> Result<bool, QueryParamError> {
+        let boxed_value = self.query_value(key);
+        if boxed_value.is_none() {
+            return Ok(false);
+        }
+
+        parse_flag(key, boxed_value.unwrap())
+    }
+
+    /// Splits values of all pairs with given key by separator and parses each item,
+    /// empty items are skipped, missing key gives empty list
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_url;
+    ///
+    /// let url_components = parse_url("https://somehost/search?id=1%2C2&id=3").unwrap();
+    ///
+    /// assert_eq!(vec![1, 2, 3], url_components.query_list::<u8>("id", ',').unwrap());
+    /// assert!(url_components.query_list::<u8>("tag", ',').unwrap().is_empty());
+    /// ```
+    pub fn query_list<T: FromStr>(&self, key: &str, separator: char) -> Result<Vec<T>, QueryParamError> where T::Err: fmt::Display {
+        if self.query.is_none() {
+            return Ok(vec![]);
+        }
+
+        let mut list : Vec<T> = vec![];
+        for value in self.query.as_ref().unwrap().get_all(key) {
+            for item in value.split(separator).filter(|item| !item.is_empty()) {
+                list.push(parse_value(key, item)?);
+            }
+        }
+        Ok(list)
+    }
+
+    fn query_value(&self, key: &str) -> Option<&str> {
+        self.query.as_ref()?.get(key)
+    }
+}
+
+pub(crate) fn parse_flag(key: &str, value: &str) -> Result<bool, QueryParamError> {
+    match value.to_ascii_lowercase().as_str() {
+        "" | "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(QueryParamError::Invalid {
+            key: key.to_string(),
+            value: value.to_string(),
+            reason: "expected one of true, false, 1, 0, yes, no, on, off".to_string()
+        }),
+    }
+}
+
+pub(crate) fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, QueryParamError> where T::Err: fmt::Display {
+    value.parse::<T>().map_err(|error| QueryParamError::Invalid {
+        key: key.to_string(),
+        value: value.to_string(),
+        reason: error.to_string()
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use crate::{parse_url, QueryParamError, UrlComponents};
+
+    #[test]
+    fn query_get_typed_values() {
+        let url_components = parse_url("https://somehost/search?page=2&ratio=0.5&ip=127.0.0.1&name=a%20b&page=3").unwrap();
+
+        assert_eq!(2, url_components.query_get::<u32>("page").unwrap());
+        assert_eq!(0.5, url_components.query_get::<f64>("ratio").unwrap());
+        assert_eq!("127.0.0.1".parse::<IpAddr>().unwrap(), url_components.query_get::<IpAddr>("ip").unwrap());
+        assert_eq!("a b", url_components.query_get::<String>("name").unwrap());
+
+        let error = url_components.query_get::<u8>("ratio").err().unwrap();
+        assert_eq!("ratio", error.key());
+        assert_eq!(QueryParamError::Invalid {
+            key: "ratio".to_string(),
+            value: "0.5".to_string(),
+            reason: "invalid digit found in string".to_string()
+        }, error);
+    }
+
+    #[test]
+    fn query_accessors_without_query() {
+        let url_components = UrlComponents::new();
+
+        assert_eq!("query parameter is missing: page", url_components.query_get::<u32>("page").err().unwrap().to_string());
+        assert_eq!(5, url_components.query_get_or("page", 5).unwrap());
+        assert!(!url_components.query_flag("debug").unwrap());
+        assert!(url_components.query_list::<String>("tag", ',').unwrap().is_empty());
+    }
+
+    #[test]
+    fn query_flag_values() {
+        let url_components = parse_url("https://somehost/?a=TRUE&b=on&c=&d=No&e=off&f=maybe").unwrap();
+
+        assert!(url_components.query_flag("a").unwrap());
+        assert!(url_components.query_flag("b").unwrap());
+        assert!(url_components.query_flag("c").unwrap());
+        assert!(!url_components.query_flag("d").unwrap());
+        assert!(!url_components.query_flag("e").unwrap());
+        assert_eq!(
+            "query parameter f has invalid value 'maybe': expected one of true, false, 1, 0, yes, no, on, off",
+            url_components.query_flag("f").err().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn query_list_and_get_or_errors() {
+        let url_components = parse_url("https://somehost/?tag=a|b||c&id=1,x&size=").unwrap();
+
+        assert_eq!(vec!["a", "b", "c"], url_components.query_list::<String>("tag", '|').unwrap());
+        assert_eq!(
+            "query parameter id has invalid value 'x': invalid digit found in string",
+            url_components.query_list::<u32>("id", ',').err().unwrap().to_string()
+        );
+        assert_eq!(
+            "query parameter size has invalid value '': cannot parse integer from empty string",
+            url_components.query_get_or::<u32>("size", 10).err().unwrap().to_string()
+        );
+    }
+}
@@ -0,0 +1,794 @@
+use std::fmt;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+use serde::{Deserialize, Deserializer, Serializer};
+use crate::{QueryParamError, UrlComponents, UrlQuery};
+use crate::query_accessors::parse_flag;
+
+impl UrlComponents {
+    /// Deserializes query into the given type, every key maps to a field
+    ///
+    /// Repeated keys are read as sequences and rejected for single values, only missing keys are read
+    /// as `None`, booleans follow [`UrlComponents::query_flag`], unit enum variants are matched by name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use url_build_parse::parse_url;
+    ///
+    /// #[derive(Deserialize, Debug, PartialEq)]
+    /// struct Search {
+    ///     page: u32,
+    ///     tag: Vec<String>,
+    ///     debug: Option<bool>,
+    /// }
+    ///
+    /// let url_components = parse_url("https://somehost/search?page=2&tag=a&tag=b").unwrap();
+    /// let search: Search = url_components.query_as().unwrap();
+    /// assert_eq!(Search { page: 2, tag: vec!["a".to_string(), "b".to_string()], debug: None }, search);
+    ///
+    /// let url_components = parse_url("https://somehost/search?page=two").unwrap();
+    /// let boxed_search = url_components.query_as::<Search>();
+    /// assert_eq!("error: query key page: invalid digit found in string", boxed_search.err().unwrap());
+    /// ```
+    pub fn query_as<'a, T: Deserialize<'a>>(&'a self) -> Result<T, String> {
+        let mut entries : Vec<(&str, Vec<&str>)> = vec![];
+        if let Some(query) = &self.query {
+            for (key, value) in query.iter() {
+                let boxed_index = entries.iter().position(|(entry_key, _)| *entry_key == key);
+                if let Some(index) = boxed_index {
+                    entries[index].1.push(value);
+                } else {
+                    entries.push((key, vec![value]));
+                }
+            }
+        }
+
+        T::deserialize(QueryDeserializer { entries, index: 0, value: None })
+            .map_err(|error| error.to_string())
+    }
+
+    /// Replaces query with serialized struct or map, sequences become repeated keys and `None` fields are skipped
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use url_build_parse::{build_url, parse_url};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Search {
+    ///     page: u32,
+    ///     tag: Vec<String>,
+    ///     debug: Option<bool>,
+    /// }
+    ///
+    /// let mut url_components = parse_url("https://somehost/search").unwrap();
+    /// let search = Search { page: 2, tag: vec!["a".to_string(), "b".to_string()], debug: None };
+    /// url_components.set_query_from(&search).unwrap();
+    ///
+    /// assert_eq!("https://somehost/search?page=2&tag=a&tag=b", build_url(url_components).unwrap());
+    /// ```
+    pub fn set_query_from<T: Serialize>(&mut self, value: &T) -> Result<(), String> {
+        let mut query = UrlQuery::new();
+        value
+            .serialize(QuerySerializer { query: &mut query })
+            .map_err(|error| error.to_string())?;
+
+        if query.is_empty() {
+            self.query = None;
+        } else {
+            self.query = Some(query);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct QuerySerdeError {
+    key: Option<String>,
+    message: String
+}
+
+impl QuerySerdeError {
+    fn with_key(mut self, key: &str) -> QuerySerdeError {
+        if self.key.is_none() {
+            self.key = Some(key.to_string());
+        }
+        self
+    }
+}
+
+impl fmt::Display for QuerySerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(key) = &self.key {
+            write!(f, "error: query key {}: {}", key, self.message)
+        } else {
+            write!(f, "error: {}", self.message)
+        }
+    }
+}
+
+impl std::error::Error for QuerySerdeError {}
+
+impl de::Error for QuerySerdeError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        QuerySerdeError { key: None, message: message.to_string() }
+    }
+}
+
+impl ser::Error for QuerySerdeError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        QuerySerdeError { key: None, message: message.to_string() }
+    }
+}
+
+fn nested_structure_error(key: &str) -> QuerySerdeError {
+    QuerySerdeError { key: Some(key.to_string()), message: "nested structures are not supported".to_string() }
+}
+
+struct QueryDeserializer<'a> {
+    entries: Vec<(&'a str, Vec<&'a str>)>,
+    index: usize,
+    value: Option<(&'a str, Vec<&'a str>)>
+}
+
+impl<'de> Deserializer<'de> for QueryDeserializer<'de> {
+    type Error = QuerySerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> MapAccess<'de> for QueryDeserializer<'de> {
+    type Error = QuerySerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        if self.index >= self.entries.len() {
+            return Ok(None);
+        }
+        let entry = std::mem::take(&mut self.entries[self.index]);
+        self.index += 1;
+
+        let key = entry.0;
+        self.value = Some(entry);
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let (key, values) = self.value.take().unwrap();
+        seed.deserialize(ValueDeserializer { key, values })
+            .map_err(|error| error.with_key(key))
+    }
+}
+
+struct ValueDeserializer<'a> {
+    key: &'a str,
+    values: Vec<&'a str>
+}
+
+impl<'a> ValueDeserializer<'a> {
+    fn value(&self) -> Result<&'a str, QuerySerdeError> {
+        if self.values.len() > 1 {
+            return Err(QuerySerdeError { key: Some(self.key.to_string()), message: "key is repeated, expected single value".to_string() });
+        }
+        Ok(self.values.first().copied().unwrap_or(""))
+    }
+
+    fn parse_flag(&self) -> Result<bool, QuerySerdeError> {
+        parse_flag(self.key, self.value()?).map_err(|error| {
+            let message = match error {
+                QueryParamError::Invalid { reason, .. } => reason,
+                QueryParamError::Missing(_) => error.to_string(),
+            };
+            QuerySerdeError { key: Some(self.key.to_string()), message }
+        })
+    }
+
+    fn parse<T: std::str::FromStr>(&self) -> Result<T, QuerySerdeError> where T::Err: fmt::Display {
+        self.value()?
+            .parse::<T>()
+            .map_err(|error| QuerySerdeError { key: Some(self.key.to_string()), message: error.to_string() })
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = QuerySerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.values.len() > 1 {
+            return self.deserialize_seq(visitor);
+        }
+        visitor.visit_borrowed_str(self.value()?)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bool(self.parse_flag()?)
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.value()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_bytes(self.value()?.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(ValueSeqAccess { key: self.key, values: self.values.into_iter() })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(nested_structure_error(self.key))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(nested_structure_error(self.key))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.value()?.into_deserializer())
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct ValueSeqAccess<'a> {
+    key: &'a str,
+    values: std::vec::IntoIter<&'a str>
+}
+
+impl<'de> SeqAccess<'de> for ValueSeqAccess<'de> {
+    type Error = QuerySerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        let boxed_value = self.values.next();
+        if boxed_value.is_none() {
+            return Ok(None);
+        }
+        seed.deserialize(ValueDeserializer { key: self.key, values: vec![boxed_value.unwrap()] }).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct QuerySerializer<'a> {
+    query: &'a mut UrlQuery
+}
+
+fn top_level_error() -> QuerySerdeError {
+    ser::Error::custom("only structs and maps can be serialized into query")
+}
+
+macro_rules! serialize_not_supported {
+    ($($method:ident($($arg:ident: $type:ty),*),)*) => {
+        $(
+            fn $method(self, $(_: $type),*) -> Result<Self::Ok, Self::Error> {
+                Err(top_level_error())
+            }
+        )*
+    };
+}
+
+impl<'a> Serializer for QuerySerializer<'a> {
+    type Ok = ();
+    type Error = QuerySerdeError;
+    type SerializeSeq = Impossible<(), QuerySerdeError>;
+    type SerializeTuple = Impossible<(), QuerySerdeError>;
+    type SerializeTupleStruct = Impossible<(), QuerySerdeError>;
+    type SerializeTupleVariant = Impossible<(), QuerySerdeError>;
+    type SerializeMap = QueryMapSerializer<'a>;
+    type SerializeStruct = QueryMapSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), QuerySerdeError>;
+
+    serialize_not_supported! {
+        serialize_bool(value: bool),
+        serialize_i8(value: i8),
+        serialize_i16(value: i16),
+        serialize_i32(value: i32),
+        serialize_i64(value: i64),
+        serialize_u8(value: u8),
+        serialize_u16(value: u16),
+        serialize_u32(value: u32),
+        serialize_u64(value: u64),
+        serialize_f32(value: f32),
+        serialize_f64(value: f64),
+        serialize_char(value: char),
+        serialize_str(value: &str),
+        serialize_bytes(value: &[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(name: &'static str),
+        serialize_unit_variant(name: &'static str, index: u32, variant: &'static str),
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(top_level_error())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(top_level_error())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(top_level_error())
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(top_level_error())
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(top_level_error())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(QueryMapSerializer { query: self.query, key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(QueryMapSerializer { query: self.query, key: None })
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(top_level_error())
+    }
+}
+
+struct QueryMapSerializer<'a> {
+    query: &'a mut UrlQuery,
+    key: Option<String>
+}
+
+impl QueryMapSerializer<'_> {
+    fn append<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> Result<(), QuerySerdeError> {
+        let values = value
+            .serialize(ValueSerializer { key, is_element: false })
+            .map_err(|error| error.with_key(key))?;
+        for value in values {
+            self.query.append(key, value.as_str());
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for QueryMapSerializer<'_> {
+    type Ok = ();
+    type Error = QuerySerdeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        let mut values = key.serialize(ValueSerializer { key: "", is_element: true })?;
+        if values.len() != 1 {
+            return Err(ser::Error::custom("query key must be a single value"));
+        }
+        self.key = values.pop();
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self.key.take().unwrap_or_default();
+        self.append(key.as_str(), value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for QueryMapSerializer<'_> {
+    type Ok = ();
+    type Error = QuerySerdeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+        self.append(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Serializes single field into list of values, empty list means the key is skipped
+struct ValueSerializer<'a> {
+    key: &'a str,
+    is_element: bool
+}
+
+macro_rules! serialize_display {
+    ($($method:ident($type:ty),)*) => {
+        $(
+            fn $method(self, value: $type) -> Result<Self::Ok, Self::Error> {
+                Ok(vec![value.to_string()])
+            }
+        )*
+    };
+}
+
+impl<'a> Serializer for ValueSerializer<'a> {
+    type Ok = Vec<String>;
+    type Error = QuerySerdeError;
+    type SerializeSeq = ValueSeqSerializer<'a>;
+    type SerializeTuple = ValueSeqSerializer<'a>;
+    type SerializeTupleStruct = ValueSeqSerializer<'a>;
+    type SerializeTupleVariant = Impossible<Vec<String>, QuerySerdeError>;
+    type SerializeMap = Impossible<Vec<String>, QuerySerdeError>;
+    type SerializeStruct = Impossible<Vec<String>, QuerySerdeError>;
+    type SerializeStructVariant = Impossible<Vec<String>, QuerySerdeError>;
+
+    serialize_display! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(vec![String::from_utf8_lossy(value).to_string()])
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(vec![variant.to_string()])
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(nested_structure_error(self.key))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        if self.is_element {
+            return Err(nested_structure_error(self.key));
+        }
+        Ok(ValueSeqSerializer { key: self.key, values: vec![] })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(nested_structure_error(self.key))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(nested_structure_error(self.key))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(nested_structure_error(self.key))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(nested_structure_error(self.key))
+    }
+}
+
+struct ValueSeqSerializer<'a> {
+    key: &'a str,
+    values: Vec<String>
+}
+
+impl ValueSeqSerializer<'_> {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), QuerySerdeError> {
+        let mut values = value.serialize(ValueSerializer { key: self.key, is_element: true })?;
+        self.values.append(&mut values);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for ValueSeqSerializer<'_> {
+    type Ok = Vec<String>;
+    type Error = QuerySerdeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.values)
+    }
+}
+
+impl ser::SerializeTuple for ValueSeqSerializer<'_> {
+    type Ok = Vec<String>;
+    type Error = QuerySerdeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.values)
+    }
+}
+
+impl ser::SerializeTupleStruct for ValueSeqSerializer<'_> {
+    type Ok = Vec<String>;
+    type Error = QuerySerdeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.values)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use serde::{Deserialize, Serialize};
+    use crate::{build_url, parse_url};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Order {
+        Asc,
+        Desc
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Search<'a> {
+        q: &'a str,
+        page: u32,
+        offset: i64,
+        ratio: f64,
+        debug: bool,
+        order: Order,
+        limit: Option<u16>,
+        #[serde(default)]
+        tag: Vec<String>,
+    }
+
+    #[test]
+    fn query_as_typed_values() {
+        let url_components = parse_url("https://somehost/search?q=a%20b&page=2&offset=-5&ratio=0.5&debug=true&order=desc&tag=x&tag=y&other=1").unwrap();
+
+        let search: Search = url_components.query_as().unwrap();
+        assert_eq!(Search {
+            q: "a b",
+            page: 2,
+            offset: -5,
+            ratio: 0.5,
+            debug: true,
+            order: Order::Desc,
+            limit: None,
+            tag: vec!["x".to_string(), "y".to_string()],
+        }, search);
+    }
+
+    #[test]
+    fn query_as_errors_name_the_key() {
+        let list = vec![
+            ("q=a&page=-1&offset=0&ratio=1&debug=true&order=asc", "error: query key page: invalid digit found in string"),
+            ("q=a&page=1&offset=0&ratio=1&debug=maybe&order=asc", "error: query key debug: expected one of true, false, 1, 0, yes, no, on, off"),
+            ("q=a&page=1&offset=0&ratio=1&debug=true&order=up", "error: query key order: unknown variant `up`, expected `asc` or `desc`"),
+            ("q=a&page=1&offset=0&ratio=1&debug=true&order=asc&limit=70000", "error: query key limit: number too large to fit in target type"),
+            ("q=a&page=1&offset=0&ratio=1&debug=true", "error: missing field `order`"),
+            ("q=a&page=1&page=2&offset=0&ratio=1&debug=true&order=asc", "error: query key page: key is repeated, expected single value"),
+            ("q=a&page=1&offset=0&ratio=1&debug=true&order=asc&limit=1&limit=2", "error: query key limit: key is repeated, expected single value"),
+        ];
+
+        for (query, expected) in list {
+            let url_components = parse_url(["https://somehost/search?", query].join("").as_str()).unwrap();
+            let boxed_search = url_components.query_as::<Search>();
+            assert_eq!(expected, boxed_search.err().unwrap(), "query: {}", query);
+        }
+    }
+
+    #[test]
+    fn query_as_missing_key_is_none() {
+        let url_components = parse_url("https://somehost/search?q=a&page=1&offset=0&ratio=1&debug=true&order=asc").unwrap();
+        let search: Search = url_components.query_as().unwrap();
+        assert_eq!(None, search.limit);
+
+        let url_components = parse_url("https://somehost/search?q=a&page=1&offset=0&ratio=1&debug=true&order=asc&limit=5").unwrap();
+        let search: Search = url_components.query_as().unwrap();
+        assert_eq!(Some(5), search.limit);
+    }
+
+    #[test]
+    fn query_as_empty_value_is_some() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Filter {
+            q: Option<String>,
+        }
+
+        let url_components = parse_url("https://somehost/search?q=").unwrap();
+        let filter: Filter = url_components.query_as().unwrap();
+        assert_eq!(Some("".to_string()), filter.q);
+
+        let url_components = parse_url("https://somehost/search?q").unwrap();
+        let filter: Filter = url_components.query_as().unwrap();
+        assert_eq!(Some("".to_string()), filter.q);
+    }
+
+    #[test]
+    fn query_as_empty_number_is_error() {
+        let url_components = parse_url("https://somehost/search?q=a&page=1&offset=0&ratio=1&debug=true&order=asc&limit=").unwrap();
+        let boxed_search = url_components.query_as::<Search>();
+        assert_eq!("error: query key limit: cannot parse integer from empty string", boxed_search.err().unwrap());
+    }
+
+    #[test]
+    fn query_as_bool_matches_query_flag() {
+        let list = vec!["debug", "debug=", "debug=true", "debug=1", "debug=YES", "debug=on", "debug=false", "debug=0", "debug=no", "debug=off"];
+
+        for query in list {
+            let url_components = parse_url(["https://somehost/search?q=a&page=1&offset=0&ratio=1&order=asc&", query].join("").as_str()).unwrap();
+            let search: Search = url_components.query_as().unwrap();
+            assert_eq!(url_components.query_flag("debug").unwrap(), search.debug, "query: {}", query);
+        }
+    }
+
+    #[test]
+    fn query_as_map_and_empty_query() {
+        let url_components = parse_url("https://somehost/search?b=2&a=1").unwrap();
+        let map: BTreeMap<String, String> = url_components.query_as().unwrap();
+        assert_eq!(vec![("a", "1"), ("b", "2")], map.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect::<Vec<(&str, &str)>>());
+
+        let url_components = parse_url("https://somehost/search").unwrap();
+        let map: BTreeMap<String, String> = url_components.query_as().unwrap();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn set_query_from_round_trip() {
+        let search = Search {
+            q: "a&b=c",
+            page: 2,
+            offset: -5,
+            ratio: 0.25,
+            debug: false,
+            order: Order::Asc,
+            limit: Some(10),
+            tag: vec!["x".to_string(), "y".to_string()],
+        };
+
+        let mut url_components = parse_url("https://somehost/search").unwrap();
+        url_components.set_query_from(&search).unwrap();
+
+        let url = build_url(url_components).unwrap();
+        assert_eq!("https://somehost/search?q=a%26b%3Dc&page=2&offset=-5&ratio=0.25&debug=false&order=asc&limit=10&tag=x&tag=y", url);
+
+        let url_components = parse_url(url.as_str()).unwrap();
+        assert_eq!(search, url_components.query_as().unwrap());
+    }
+
+    #[test]
+    fn set_query_from_errors() {
+        #[derive(Serialize)]
+        struct Nested {
+            filter: BTreeMap<String, String>,
+        }
+
+        let mut url_components = parse_url("https://somehost/search?a=1").unwrap();
+
+        let boxed_result = url_components.set_query_from(&Nested { filter: BTreeMap::new() });
+        assert_eq!("error: query key filter: nested structures are not supported", boxed_result.err().unwrap());
+
+        let boxed_result = url_components.set_query_from(&vec![1, 2]);
+        assert_eq!("error: only structs and maps can be serialized into query", boxed_result.err().unwrap());
+
+        url_components.set_query_from(&BTreeMap::<String, String>::new()).unwrap();
+        assert!(url_components.query.is_none());
+    }
+}
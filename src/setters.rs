@@ -0,0 +1,290 @@
+use crate::percent_encoding::{is_fragment_char, is_path_char, is_reg_name_char, is_userinfo_char, normalize_percent_encoding};
+use crate::validation::{is_username_char, is_valid_host, is_valid_scheme};
+use crate::{parse_query_params, QueryEncoding, UrlAuthority, UrlComponents, UrlUserInfo, UrlValidationError};
+
+/// In-place mutation modeled after [WHATWG URL setters](https://url.spec.whatwg.org/#urlutils-members).
+///
+/// Each setter normalizes given value and refuses transitions which would leave
+/// UrlComponents in a state [build_url](crate::build_url) can't serialize.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_url, parse_url};
+///
+/// let mut url_components = parse_url("https://usr@somehost:80/path?q=1#top").unwrap();
+///
+/// url_components.set_host(Some("Other.Host")).unwrap();
+/// url_components.set_port(Some(8080)).unwrap();
+/// url_components.set_path("new path").unwrap();
+/// url_components.set_query(Some("?q=2")).unwrap();
+/// url_components.set_fragment(None).unwrap();
+///
+/// assert!(url_components.set_host(None).is_err());
+/// assert_eq!("https://usr@other.host:8080/new%20path?q=2", build_url(url_components).unwrap());
+/// ```
+impl UrlComponents {
+    /// Sets scheme, trailing `:` is ignored and scheme is lowercased.
+    pub fn set_scheme(&mut self, scheme: &str) -> Result<(), UrlValidationError> {
+        let scheme = scheme.strip_suffix(':').unwrap_or(scheme);
+        if scheme.is_empty() {
+            return Err(UrlValidationError::EmptyScheme);
+        }
+        if !is_valid_scheme(scheme) {
+            return Err(UrlValidationError::InvalidScheme(scheme.to_string()));
+        }
+
+        self.scheme = scheme.to_ascii_lowercase();
+        Ok(())
+    }
+
+    /// Sets host, `None` removes authority. Host is lowercased, not allowed characters are percent-encoded.
+    pub fn set_host(&mut self, host: Option<&str>) -> Result<(), UrlValidationError> {
+        if host.is_none() {
+            if let Some(authority) = &self.authority {
+                if authority.user_info.is_some() || authority.port.is_some() {
+                    return Err(UrlValidationError::MissingHost);
+                }
+            }
+            if self.path.starts_with("//") {
+                return Err(UrlValidationError::PathStartsWithDoubleSlash(self.path.to_string()));
+            }
+
+            self.authority = None;
+            return Ok(());
+        }
+
+        let host = host.unwrap();
+        let mut normalized_host = host.to_ascii_lowercase();
+        if !host.starts_with('[') {
+            normalized_host = normalize_percent_encoding(normalized_host.as_str(), is_reg_name_char);
+        }
+        if !is_valid_host(normalized_host.as_str()) {
+            return Err(UrlValidationError::InvalidHost(host.to_string()));
+        }
+
+        if let Some(authority) = self.authority.as_mut() {
+            authority.host = normalized_host;
+        } else {
+            if !self.path.is_empty() && !self.path.starts_with('/') {
+                return Err(UrlValidationError::RelativePathWithAuthority(self.path.to_string()));
+            }
+            self.authority = Some(UrlAuthority { user_info: None, host: normalized_host, port: None });
+        }
+
+        Ok(())
+    }
+
+    /// Sets port, `None` removes it. Requires host to be defined.
+    pub fn set_port(&mut self, port: Option<usize>) -> Result<(), UrlValidationError> {
+        if let Some(port) = port.filter(|port| *port > u16::MAX as usize) {
+            return Err(UrlValidationError::InvalidPort(port));
+        }
+
+        let authority = self.authority_mut()?;
+        authority.port = port;
+        Ok(())
+    }
+
+    /// Sets username, not allowed characters are percent-encoded. Requires host to be defined.
+    ///
+    /// Empty username removes user info, unless password is defined.
+    pub fn set_username(&mut self, username: &str) -> Result<(), UrlValidationError> {
+        let username = normalize_percent_encoding(username, is_username_char);
+
+        let authority = self.authority_mut()?;
+        if authority.user_info.is_none() {
+            if !username.is_empty() {
+                authority.user_info = Some(UrlUserInfo { username, password: None });
+            }
+            return Ok(());
+        }
+
+        let user_info = authority.user_info.as_mut().unwrap();
+        if username.is_empty() && user_info.password.is_none() {
+            authority.user_info = None;
+        } else {
+            user_info.username = username;
+        }
+        Ok(())
+    }
+
+    /// Sets password, `None` removes it. Not allowed characters are percent-encoded. Requires host to be defined.
+    pub fn set_password(&mut self, password: Option<&str>) -> Result<(), UrlValidationError> {
+        let password = password.map(|password| normalize_percent_encoding(password, is_userinfo_char));
+
+        let authority = self.authority_mut()?;
+        if authority.user_info.is_none() {
+            if password.is_some() {
+                authority.user_info = Some(UrlUserInfo { username: "".to_string(), password });
+            }
+            return Ok(());
+        }
+
+        let user_info = authority.user_info.as_mut().unwrap();
+        if password.is_none() && user_info.username.is_empty() {
+            authority.user_info = None;
+        } else {
+            user_info.password = password;
+        }
+        Ok(())
+    }
+
+    /// Sets path, not allowed characters are percent-encoded.
+    ///
+    /// `/` is prepended if authority is defined, path starting with `//` is refused otherwise.
+    pub fn set_path(&mut self, path: &str) -> Result<(), UrlValidationError> {
+        let mut path = normalize_percent_encoding(path, is_path_char);
+
+        if self.authority.is_some() {
+            if !path.is_empty() && !path.starts_with('/') {
+                path = ["/".to_string(), path].join("");
+            }
+        } else if path.starts_with("//") {
+            return Err(UrlValidationError::PathStartsWithDoubleSlash(path));
+        }
+
+        self.path = path;
+        Ok(())
+    }
+
+    /// Sets query from the query string, leading `?` is ignored. `None` removes query.
+    pub fn set_query(&mut self, query: Option<&str>) -> Result<(), UrlValidationError> {
+        if query.is_none() {
+            self.query = None;
+            return Ok(());
+        }
+
+        let query = query.unwrap();
+        let query = query.strip_prefix('?').unwrap_or(query);
+        self.query = Some(parse_query_params(query, QueryEncoding::Rfc3986));
+        Ok(())
+    }
+
+    /// Sets fragment, leading `#` is ignored and not allowed characters are percent-encoded. `None` removes fragment.
+    pub fn set_fragment(&mut self, fragment: Option<&str>) -> Result<(), UrlValidationError> {
+        if fragment.is_none() {
+            self.fragment = None;
+            return Ok(());
+        }
+
+        let fragment = fragment.unwrap();
+        let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+        self.fragment = Some(normalize_percent_encoding(fragment, is_fragment_char));
+        Ok(())
+    }
+
+    fn authority_mut(&mut self) -> Result<&mut UrlAuthority, UrlValidationError> {
+        self.authority.as_mut().ok_or(UrlValidationError::MissingHost)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_url, parse_url, UrlComponents, UrlValidationError};
+
+    #[test]
+    fn set_scheme() {
+        let mut url_components = parse_url("http://somehost").unwrap();
+
+        url_components.set_scheme("HTTPS:").unwrap();
+        assert_eq!("https", url_components.scheme);
+
+        assert_eq!(Err(UrlValidationError::EmptyScheme), url_components.set_scheme(":"));
+        assert_eq!(Err(UrlValidationError::InvalidScheme("1http".to_string())), url_components.set_scheme("1http"));
+        assert_eq!("https", url_components.scheme);
+    }
+
+    #[test]
+    fn set_host() {
+        let mut url_components = parse_url("http://somehost/path").unwrap();
+
+        url_components.set_host(Some("Some Host")).unwrap();
+        assert_eq!("some%20host", url_components.authority.as_ref().unwrap().host);
+
+        url_components.set_host(Some("[2001:DB8::7]")).unwrap();
+        assert_eq!("[2001:db8::7]", url_components.authority.as_ref().unwrap().host);
+
+        assert_eq!(Err(UrlValidationError::InvalidHost("[::1".to_string())), url_components.set_host(Some("[::1")));
+
+        url_components.set_host(None).unwrap();
+        assert!(url_components.authority.is_none());
+        assert_eq!("http:/path", build_url(url_components).unwrap());
+    }
+
+    #[test]
+    fn set_host_refuses_invalid_transitions() {
+        let mut url_components = parse_url("http://usr@somehost/path").unwrap();
+        assert_eq!(Err(UrlValidationError::MissingHost), url_components.set_host(None));
+
+        let mut url_components = parse_url("http://somehost//path").unwrap();
+        assert_eq!(Err(UrlValidationError::PathStartsWithDoubleSlash("//path".to_string())), url_components.set_host(None));
+
+        let mut url_components = parse_url("mailto:user@host").unwrap();
+        assert_eq!(Err(UrlValidationError::RelativePathWithAuthority("user@host".to_string())), url_components.set_host(Some("host")));
+        assert!(url_components.authority.is_none());
+    }
+
+    #[test]
+    fn set_port() {
+        let mut url_components = parse_url("http://somehost:80").unwrap();
+
+        url_components.set_port(Some(8080)).unwrap();
+        assert_eq!(Some(8080), url_components.authority.as_ref().unwrap().port);
+
+        assert_eq!(Err(UrlValidationError::InvalidPort(65536)), url_components.set_port(Some(65536)));
+
+        url_components.set_port(None).unwrap();
+        assert_eq!("http://somehost", build_url(url_components).unwrap());
+
+        let mut url_components = parse_url("news:comp.infosystems").unwrap();
+        assert_eq!(Err(UrlValidationError::MissingHost), url_components.set_port(Some(80)));
+    }
+
+    #[test]
+    fn set_username_and_password() {
+        let mut url_components = parse_url("http://somehost").unwrap();
+
+        url_components.set_username("us:r").unwrap();
+        url_components.set_password(Some("p@ss:word")).unwrap();
+        assert_eq!("http://us%3Ar:p%40ss:word@somehost", build_url(url_components.clone()).unwrap());
+
+        url_components.set_username("").unwrap();
+        assert_eq!("http://:p%40ss:word@somehost", build_url(url_components.clone()).unwrap());
+
+        url_components.set_password(None).unwrap();
+        assert!(url_components.authority.as_ref().unwrap().user_info.is_none());
+
+        let mut url_components = UrlComponents::new();
+        assert_eq!(Err(UrlValidationError::MissingHost), url_components.set_username("usr"));
+        assert_eq!(Err(UrlValidationError::MissingHost), url_components.set_password(Some("pwd")));
+    }
+
+    #[test]
+    fn set_path() {
+        let mut url_components = parse_url("http://somehost").unwrap();
+
+        url_components.set_path("a b%20c?#").unwrap();
+        assert_eq!("/a%20b%20c%3F%23", url_components.path);
+
+        let mut url_components = parse_url("news:comp").unwrap();
+        url_components.set_path("comp.infosystems").unwrap();
+        assert_eq!("comp.infosystems", url_components.path);
+        assert_eq!(Err(UrlValidationError::PathStartsWithDoubleSlash("//comp".to_string())), url_components.set_path("//comp"));
+    }
+
+    #[test]
+    fn set_query_and_fragment() {
+        let mut url_components = parse_url("http://somehost/?q=1#top").unwrap();
+
+        url_components.set_query(Some("?a=1%262&b")).unwrap();
+        url_components.set_fragment(Some("#new top")).unwrap();
+        assert_eq!("1&2", url_components.query.as_ref().unwrap().get("a").unwrap());
+        assert_eq!("new%20top", url_components.fragment.as_ref().unwrap());
+
+        url_components.set_query(None).unwrap();
+        url_components.set_fragment(None).unwrap();
+        assert_eq!("http://somehost/", build_url(url_components).unwrap());
+    }
+}
@@ -0,0 +1,480 @@
+use crate::percent_encoding::{is_unreserved, percent_decode_to_string, percent_encode};
+
+/// Decoded `tel:` URI, see [RFC 3966](https://www.rfc-editor.org/rfc/rfc3966)
+///
+/// - `number`: telephone subscriber as written with `%23` decoded to `#`, global numbers start with `+`
+/// - `extension`, `isdn_subaddress`, `phone_context`: `ext`, `isub` and `phone-context` parameters
+/// - `parameters`: any other parameters in order, names are lowercased, values are percent-decoded
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct TelUrl {
+    pub number: String,
+    pub extension: Option<String>,
+    pub isdn_subaddress: Option<String>,
+    pub phone_context: Option<String>,
+    pub parameters: Vec<(String, Option<String>)>
+}
+
+impl TelUrl {
+    /// Creates tel URI with given number and no parameters
+    pub fn new(number: &str) -> TelUrl {
+        TelUrl {
+            number: number.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if number starts with `+`
+    pub fn is_global(&self) -> bool {
+        self.number.starts_with('+')
+    }
+
+    /// Returns number without visual separators `-`, `.`, `(` and `)`, hex digits are uppercased
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::TelUrl;
+    ///
+    /// assert_eq!("+18165551212", TelUrl::new("+1-816-555-1212").digits());
+    /// assert_eq!("7042", TelUrl::new("(70)42").digits());
+    /// ```
+    pub fn digits(&self) -> String {
+        remove_visual_separators(self.number.as_str()).to_ascii_uppercase()
+    }
+
+    /// Compares tel URIs following [RFC 3966 section 4](https://www.rfc-editor.org/rfc/rfc3966#section-4)
+    ///
+    /// Numbers and extensions are compared without visual separators, domain phone contexts,
+    /// `isub` and parameters ignore case, parameter order does not matter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_tel_url;
+    ///
+    /// let tel_url = parse_tel_url("tel:+1-201-555-0123;ext=1-2").unwrap();
+    /// assert!(tel_url.is_equivalent(&parse_tel_url("tel:+1(201)5550123;EXT=12").unwrap()));
+    /// assert!(!tel_url.is_equivalent(&parse_tel_url("tel:+12015550123").unwrap()));
+    ///
+    /// let tel_url = parse_tel_url("tel:7042;phone-context=Example.COM").unwrap();
+    /// assert!(tel_url.is_equivalent(&parse_tel_url("tel:70-42;phone-context=example.com").unwrap()));
+    /// ```
+    pub fn is_equivalent(&self, other: &TelUrl) -> bool {
+        if self.is_global() != other.is_global() || self.digits() != other.digits() {
+            return false;
+        }
+
+        let extension = self.extension.as_deref().map(remove_visual_separators);
+        let other_extension = other.extension.as_deref().map(remove_visual_separators);
+        let isdn_subaddress = self.isdn_subaddress.as_deref().map(str::to_lowercase);
+        let other_isdn_subaddress = other.isdn_subaddress.as_deref().map(str::to_lowercase);
+        if extension != other_extension || isdn_subaddress != other_isdn_subaddress {
+            return false;
+        }
+
+        let phone_context = self.phone_context.as_deref().map(normalize_phone_context);
+        let other_phone_context = other.phone_context.as_deref().map(normalize_phone_context);
+        if phone_context != other_phone_context {
+            return false;
+        }
+
+        let mut parameters = lowercase_parameters(&self.parameters);
+        let mut other_parameters = lowercase_parameters(&other.parameters);
+        parameters.sort();
+        other_parameters.sort();
+        parameters == other_parameters
+    }
+}
+
+/// Parses `tel:` URI
+///
+/// Global number is `+` followed by digits, local number consists of hex digits, `*` and `#`
+/// and requires `phone-context`, `#` is written as `%23` and decoded in `number`.
+/// Visual separators are kept in `number` as written, each parameter may appear only once,
+/// `phone-context` is not allowed for global number.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::parse_tel_url;
+///
+/// let tel_url = parse_tel_url("tel:+1-816-555-1212;ext=101;isub=8978").unwrap();
+/// assert!(tel_url.is_global());
+/// assert_eq!("+1-816-555-1212", tel_url.number);
+/// assert_eq!("101", tel_url.extension.unwrap());
+/// assert_eq!("8978", tel_url.isdn_subaddress.unwrap());
+///
+/// let tel_url = parse_tel_url("tel:863-1234;phone-context=+1-914-555").unwrap();
+/// assert!(!tel_url.is_global());
+/// assert_eq!("+1-914-555", tel_url.phone_context.unwrap());
+///
+/// assert_eq!(
+///     "error: local tel number requires phone-context: 863-1234",
+///     parse_tel_url("tel:863-1234").err().unwrap()
+/// );
+/// ```
+pub fn parse_tel_url(url: &str) -> Result<TelUrl, String> {
+    let boxed_scheme = url.split_once(':');
+    if boxed_scheme.is_none() || !boxed_scheme.unwrap().0.eq_ignore_ascii_case("tel") {
+        return Err(["error: not a tel URI: ", url].join(""));
+    }
+
+    let mut parts = boxed_scheme.unwrap().1.split(';');
+    let encoded_number = parts.next().unwrap_or("");
+    let number = percent_decode_to_string(encoded_number);
+    let number = number.as_str();
+
+    let is_valid_number = if let Some(global_digits) = number.strip_prefix('+') {
+        is_valid_phone_digits(global_digits, |char| char.is_ascii_digit())
+    } else {
+        is_valid_phone_digits(number, |char| char.is_ascii_hexdigit() || char == '*' || char == '#')
+    };
+    if !is_valid_number {
+        return Err(["error: tel number is not valid: ", encoded_number].join(""));
+    }
+
+    let mut tel_url = TelUrl::new(number);
+
+    for parameter in parts {
+        let (name, value) = match parameter.split_once('=') {
+            Some((name, value)) => (name.to_ascii_lowercase(), Some(percent_decode_to_string(value))),
+            None => (parameter.to_ascii_lowercase(), None),
+        };
+
+        if !is_valid_parameter_name(name.as_str()) {
+            return Err(["error: tel parameter name is not valid: ", parameter].join(""));
+        }
+
+        let is_duplicate = match name.as_str() {
+            "ext" => tel_url.extension.is_some(),
+            "isub" => tel_url.isdn_subaddress.is_some(),
+            "phone-context" => tel_url.phone_context.is_some(),
+            _ => tel_url.parameters.iter().any(|(existing_name, _)| *existing_name == name),
+        };
+        if is_duplicate {
+            return Err(["error: tel parameter is defined more than once: ", name.as_str()].join(""));
+        }
+
+        if name == "ext" || name == "isub" || name == "phone-context" {
+            if value.is_none() || value.as_ref().unwrap().is_empty() {
+                return Err(["error: tel parameter has no value: ", parameter].join(""));
+            }
+            let value = value.unwrap();
+
+            if name == "ext" {
+                if !is_valid_phone_digits(value.as_str(), |char| char.is_ascii_digit()) {
+                    return Err(["error: tel extension is not valid: ", value.as_str()].join(""));
+                }
+                tel_url.extension = Some(value);
+            } else if name == "isub" {
+                tel_url.isdn_subaddress = Some(value);
+            } else {
+                if !is_valid_phone_context(value.as_str()) {
+                    return Err(["error: tel phone-context is not valid: ", value.as_str()].join(""));
+                }
+                tel_url.phone_context = Some(value);
+            }
+            continue;
+        }
+
+        tel_url.parameters.push((name, value));
+    }
+
+    if !tel_url.is_global() && tel_url.phone_context.is_none() {
+        return Err(["error: local tel number requires phone-context: ", encoded_number].join(""));
+    }
+    if tel_url.is_global() && tel_url.phone_context.is_some() {
+        return Err(["error: global tel number cannot have phone-context: ", encoded_number].join(""));
+    }
+
+    Ok(tel_url)
+}
+
+/// Builds `tel:` URI, parameters are written in order recommended by
+/// [RFC 3966 section 5.4](https://www.rfc-editor.org/rfc/rfc3966#section-5.4):
+/// `isub` and `ext` first, then `phone-context`, then other parameters sorted by name ignoring case.
+/// `#` in the number is written as `%23`, parameter values are percent-encoded, parameter names
+/// must consist of letters, digits and `-`.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_tel_url, TelUrl};
+///
+/// let tel_url = TelUrl {
+///     extension: Some("22".to_string()),
+///     parameters: vec![("tgrp".to_string(), Some("a b".to_string())), ("foo".to_string(), None)],
+///     ..TelUrl::new("+1-816-555-1212")
+/// };
+/// assert_eq!("tel:+1-816-555-1212;ext=22;foo;tgrp=a%20b", build_tel_url(&tel_url).unwrap());
+///
+/// let tel_url = TelUrl { parameters: vec![("a;b".to_string(), None)], ..TelUrl::new("+1") };
+/// assert_eq!("error: tel parameter name is not valid: a;b", build_tel_url(&tel_url).err().unwrap());
+/// ```
+pub fn build_tel_url(tel_url: &TelUrl) -> Result<String, String> {
+    let number = percent_encode(tel_url.number.as_str(), is_number_char);
+    let mut url = ["tel:", number.as_str()].join("");
+
+    if let Some(isdn_subaddress) = &tel_url.isdn_subaddress {
+        let isdn_subaddress = percent_encode(isdn_subaddress, is_param_char);
+        url = [url.as_str(), ";isub=", isdn_subaddress.as_str()].join("");
+    }
+    if let Some(extension) = &tel_url.extension {
+        let extension = percent_encode(extension, is_param_char);
+        url = [url.as_str(), ";ext=", extension.as_str()].join("");
+    }
+    if let Some(phone_context) = &tel_url.phone_context {
+        let phone_context = percent_encode(phone_context, is_param_char);
+        url = [url.as_str(), ";phone-context=", phone_context.as_str()].join("");
+    }
+
+    let mut parameters = tel_url.parameters.to_vec();
+    parameters.sort_by(|(name, _), (other_name, _)| {
+        (name.to_ascii_lowercase(), name).cmp(&(other_name.to_ascii_lowercase(), other_name))
+    });
+    for (name, value) in parameters.iter() {
+        if !is_valid_parameter_name(name) {
+            return Err(["error: tel parameter name is not valid: ", name.as_str()].join(""));
+        }
+        url = [url.as_str(), ";", name.as_str()].join("");
+        if let Some(value) = value {
+            url = [url.as_str(), "=", percent_encode(value, is_param_char).as_str()].join("");
+        }
+    }
+
+    Ok(url)
+}
+
+fn is_visual_separator(char: char) -> bool {
+    matches!(char, '-' | '.' | '(' | ')')
+}
+
+fn remove_visual_separators(input: &str) -> String {
+    input.chars().filter(|char| !is_visual_separator(*char)).collect()
+}
+
+fn is_valid_phone_digits(input: &str, is_digit: fn(char) -> bool) -> bool {
+    input.chars().any(is_digit) && input.chars().all(|char| is_digit(char) || is_visual_separator(char))
+}
+
+fn is_valid_parameter_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|char| char.is_ascii_alphanumeric() || char == '-')
+}
+
+fn is_valid_phone_context(value: &str) -> bool {
+    if let Some(global_digits) = value.strip_prefix('+') {
+        return is_valid_phone_digits(global_digits, |char| char.is_ascii_digit());
+    }
+
+    let domain = value.strip_suffix('.').unwrap_or(value);
+    !domain.is_empty() && domain.split('.').all(|label| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|char| char.is_ascii_alphanumeric() || char == '-')
+    })
+}
+
+fn lowercase_parameters(parameters: &[(String, Option<String>)]) -> Vec<(String, Option<String>)> {
+    parameters.iter()
+        .map(|(name, value)| (name.to_string(), value.as_deref().map(str::to_lowercase)))
+        .collect()
+}
+
+fn normalize_phone_context(value: &str) -> String {
+    if value.starts_with('+') {
+        return remove_visual_separators(value);
+    }
+    value.to_ascii_lowercase()
+}
+
+fn is_number_char(byte: u8) -> bool {
+    is_unreserved(byte) || matches!(byte, b'+' | b'*' | b'(' | b')')
+}
+
+fn is_param_char(byte: u8) -> bool {
+    is_unreserved(byte) || matches!(byte, b'[' | b']' | b'/' | b':' | b'&' | b'+' | b'$')
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_tel_url, parse_tel_url, parse_url, TelUrl};
+
+    #[test]
+    fn parse_tel_rfc_examples() {
+        let tel_url = parse_tel_url("tel:+1-201-555-0123").unwrap();
+        assert_eq!(TelUrl::new("+1-201-555-0123"), tel_url);
+        assert_eq!("+12015550123", tel_url.digits());
+
+        let tel_url = parse_tel_url("tel:7042;phone-context=example.com").unwrap();
+        assert!(!tel_url.is_global());
+        assert_eq!("example.com", tel_url.phone_context.unwrap());
+
+        let tel_url = parse_tel_url("TEL:*1a%23;Phone-Context=+49;Tgrp=TG-1;trunk-context=%5Bx%5D;npdi").unwrap();
+        assert_eq!("*1A#", tel_url.digits());
+        assert_eq!("+49", tel_url.phone_context.unwrap());
+        assert_eq!(vec![
+            ("tgrp".to_string(), Some("TG-1".to_string())),
+            ("trunk-context".to_string(), Some("[x]".to_string())),
+            ("npdi".to_string(), None),
+        ], tel_url.parameters);
+    }
+
+    #[test]
+    fn parse_tel_other_scheme() {
+        let boxed_tel_url = parse_tel_url("mailto:a@x");
+
+        assert!(boxed_tel_url.is_err());
+        assert_eq!("error: not a tel URI: mailto:a@x", boxed_tel_url.err().unwrap());
+    }
+
+    #[test]
+    fn parse_tel_invalid_number() {
+        assert_eq!("error: tel number is not valid: ", parse_tel_url("tel:").err().unwrap());
+        assert_eq!("error: tel number is not valid: +", parse_tel_url("tel:+").err().unwrap());
+        assert_eq!("error: tel number is not valid: +1a", parse_tel_url("tel:+1a").err().unwrap());
+        assert_eq!("error: tel number is not valid: --", parse_tel_url("tel:--").err().unwrap());
+    }
+
+    #[test]
+    fn parse_tel_duplicate_parameter() {
+        assert_eq!("error: tel parameter is defined more than once: ext", parse_tel_url("tel:+1;ext=1;ext=2").err().unwrap());
+        assert_eq!("error: tel parameter is defined more than once: x", parse_tel_url("tel:+1;x=1;X=2").err().unwrap());
+    }
+
+    #[test]
+    fn parse_tel_invalid_parameter() {
+        assert_eq!("error: tel parameter has no value: ext=", parse_tel_url("tel:+1;ext=").err().unwrap());
+        assert_eq!("error: tel extension is not valid: 1a", parse_tel_url("tel:+1;ext=1a").err().unwrap());
+        assert_eq!("error: tel parameter name is not valid: =1", parse_tel_url("tel:+1;=1").err().unwrap());
+        assert_eq!("error: tel phone-context is not valid: -bad-.com", parse_tel_url("tel:1;phone-context=-bad-.com").err().unwrap());
+    }
+
+    #[test]
+    fn parse_tel_local_number_without_phone_context() {
+        let boxed_tel_url = parse_tel_url("tel:1;ext=2");
+
+        assert!(boxed_tel_url.is_err());
+        assert_eq!("error: local tel number requires phone-context: 1", boxed_tel_url.err().unwrap());
+    }
+
+    #[test]
+    fn parse_tel_global_number_with_phone_context() {
+        let boxed_tel_url = parse_tel_url("tel:+1;phone-context=+49");
+
+        assert!(boxed_tel_url.is_err());
+        assert_eq!("error: global tel number cannot have phone-context: +1", boxed_tel_url.err().unwrap());
+    }
+
+    #[test]
+    fn tel_equivalence_ignores_visual_separators() {
+        let tel_url = parse_tel_url("tel:+1-201-555-0123").unwrap();
+        assert!(tel_url.is_equivalent(&parse_tel_url("tel:+1.201.555.0123").unwrap()));
+        assert!(!tel_url.is_equivalent(&parse_tel_url("tel:+1-201-555-0124").unwrap()));
+
+        let tel_url = parse_tel_url("tel:+1(201)555-0123;ext=1").unwrap();
+        assert!(tel_url.is_equivalent(&parse_tel_url("tel:+12015550123;EXT=1").unwrap()));
+
+        let tel_url = parse_tel_url("tel:ab12;phone-context=+1-914-555").unwrap();
+        assert!(tel_url.is_equivalent(&parse_tel_url("tel:AB-12;phone-context=+1914555").unwrap()));
+    }
+
+    #[test]
+    fn tel_equivalence_ignores_case() {
+        let tel_url = parse_tel_url("tel:+1;isub=ab").unwrap();
+        assert!(tel_url.is_equivalent(&parse_tel_url("tel:+1;isub=AB").unwrap()));
+        assert!(tel_url.is_equivalent(&parse_tel_url("tel:+1;isub=%41b").unwrap()));
+
+        let tel_url = parse_tel_url("tel:+1;tgrp=TG-1").unwrap();
+        assert!(tel_url.is_equivalent(&parse_tel_url("tel:+1;TGRP=tg-1").unwrap()));
+    }
+
+    #[test]
+    fn tel_equivalence_ignores_parameter_order() {
+        let tel_url = parse_tel_url("tel:+1;a=1;b").unwrap();
+
+        assert!(tel_url.is_equivalent(&parse_tel_url("tel:+1;b;A=1").unwrap()));
+    }
+
+    #[test]
+    fn tel_equivalence_global_and_local_number_differ() {
+        let tel_url = parse_tel_url("tel:+7042").unwrap();
+
+        assert!(!tel_url.is_equivalent(&parse_tel_url("tel:7042;phone-context=example.com").unwrap()));
+    }
+
+    #[test]
+    fn tel_equivalence_parameters_differ() {
+        assert!(!parse_tel_url("tel:+1;ext=1").unwrap().is_equivalent(&parse_tel_url("tel:+1").unwrap()));
+        assert!(!parse_tel_url("tel:1;phone-context=a.com").unwrap().is_equivalent(&parse_tel_url("tel:1;phone-context=b.com").unwrap()));
+        assert!(!parse_tel_url("tel:+1;a=1").unwrap().is_equivalent(&parse_tel_url("tel:+1;a=2").unwrap()));
+        assert!(!parse_tel_url("tel:+1;a").unwrap().is_equivalent(&parse_tel_url("tel:+1;a=").unwrap()));
+    }
+
+    #[test]
+    fn tel_local_number_with_hash() {
+        let tel_url = parse_tel_url("tel:*1%23;phone-context=example.com").unwrap();
+        assert_eq!("*1#", tel_url.number);
+        assert_eq!("tel:*1%23;phone-context=example.com", build_tel_url(&tel_url).unwrap());
+
+        let tel_url = TelUrl { phone_context: Some("+49".to_string()), ..TelUrl::new("#31#") };
+        let url = build_tel_url(&tel_url).unwrap();
+        assert_eq!("tel:%2331%23;phone-context=+49", url);
+        assert_eq!(None, parse_url(url.as_str()).unwrap().fragment);
+        assert_eq!(tel_url, parse_tel_url(url.as_str()).unwrap());
+    }
+
+    #[test]
+    fn tel_round_trip_global_number() {
+        let url = "tel:+1-816-555-1212;isub=1234;ext=5";
+
+        assert_eq!(url, build_tel_url(&parse_tel_url(url).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn tel_round_trip_local_number() {
+        let url = "tel:863-1234;phone-context=+1-914-555";
+
+        assert_eq!(url, build_tel_url(&parse_tel_url(url).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn tel_round_trip_sorts_other_parameters() {
+        let url = "tel:%2331%23;phone-context=example.com;a=b%20c;z";
+
+        assert_eq!(url, build_tel_url(&parse_tel_url(url).unwrap()).unwrap());
+        assert_eq!(url, build_tel_url(&parse_tel_url("tel:%2331%23;z;a=b%20c;phone-context=example.com").unwrap()).unwrap());
+    }
+
+    #[test]
+    fn build_tel_sorts_parameters_ignoring_case() {
+        let tel_url = TelUrl {
+            parameters: vec![("b".to_string(), None), ("a".to_string(), None), ("C".to_string(), None), ("A".to_string(), None)],
+            ..TelUrl::new("+1")
+        };
+
+        assert_eq!("tel:+1;A;a;b;C", build_tel_url(&tel_url).unwrap());
+    }
+
+    #[test]
+    fn build_tel_encodes_extension_and_phone_context() {
+        let tel_url = TelUrl {
+            extension: Some("1;x=2".to_string()),
+            phone_context: Some("a b".to_string()),
+            ..TelUrl::new("1")
+        };
+
+        assert_eq!("tel:1;ext=1%3Bx%3D2;phone-context=a%20b", build_tel_url(&tel_url).unwrap());
+    }
+
+    #[test]
+    fn build_tel_invalid_parameter_name() {
+        let list = vec!["", "a=b", "a;b", "a b"];
+
+        for name in list {
+            let tel_url = TelUrl { parameters: vec![(name.to_string(), Some("1".to_string()))], ..TelUrl::new("+1") };
+            assert_eq!(["error: tel parameter name is not valid: ", name].join(""), build_tel_url(&tel_url).err().unwrap(), "name: {}", name);
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use crate::percent_encoding::{is_path_char, is_reg_name_char, is_valid_encoded, percent_decode_to_string, percent_encode};
+use crate::validation::is_valid_scheme;
+use crate::{build_query_params, parse_query_params, QueryEncoding, UrlQuery};
+
+/// URL addressing a server behind a Unix domain socket
+///
+/// - `scheme`: `unix` or transport followed by `+unix`, like `http+unix`
+/// - `socket_path`: percent-decoded path of the socket file
+/// - `path`: path of the request sent over the socket, as written
+/// - `query`: query of the request
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct UnixSocketUrl {
+    pub scheme: String,
+    pub socket_path: String,
+    pub path: String,
+    pub query: Option<UrlQuery>
+}
+
+impl UnixSocketUrl {
+    /// Creates `http+unix` URL with given socket path and request path
+    pub fn new(socket_path: &str, path: &str) -> UnixSocketUrl {
+        UnixSocketUrl {
+            scheme: "http+unix".to_string(),
+            socket_path: socket_path.to_string(),
+            path: path.to_string(),
+            query: None
+        }
+    }
+
+    /// Returns protocol spoken over the socket, like `http` for `http+unix`, `None` for plain `unix`
+    pub fn transport_scheme(&self) -> Option<&str> {
+        let scheme = self.scheme.as_str();
+        let transport_end = scheme.len().checked_sub(5)?;
+        if !scheme.get(transport_end..)?.eq_ignore_ascii_case("+unix") {
+            return None;
+        }
+        Some(&scheme[..transport_end])
+    }
+
+    /// Returns request target to send over the socket, path defaults to `/` and always starts with it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_unix_socket_url;
+    ///
+    /// let unix_socket_url = parse_unix_socket_url("http+unix://%2Fvar%2Frun%2Fdocker.sock/containers/json?all=1").unwrap();
+    /// assert_eq!("/containers/json?all=1", unix_socket_url.request_target());
+    ///
+    /// let unix_socket_url = parse_unix_socket_url("unix:///run/app.sock").unwrap();
+    /// assert_eq!("/", unix_socket_url.request_target());
+    /// ```
+    pub fn request_target(&self) -> String {
+        let mut request_target = self.path.to_string();
+        if !request_target.starts_with('/') {
+            request_target = ["/", request_target.as_str()].join("");
+        }
+        if let Some(query) = &self.query {
+            let query = build_query_params(query, QueryEncoding::Rfc3986);
+            request_target = [request_target.as_str(), "?", query.as_str()].join("");
+        }
+        request_target
+    }
+}
+
+/// Parses Unix socket URL, fragment is ignored
+///
+/// With `+unix` schemes authority is the percent-encoded socket path and the rest is request path.
+/// With `unix` scheme socket path is taken from the path if authority is empty (`unix:///run/app.sock`),
+/// otherwise it is read from the authority the same way.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::parse_unix_socket_url;
+///
+/// let unix_socket_url = parse_unix_socket_url("http+unix://%2Fvar%2Frun%2Fdocker.sock/v1.41/containers/json").unwrap();
+/// assert_eq!("http", unix_socket_url.transport_scheme().unwrap());
+/// assert_eq!("/var/run/docker.sock", unix_socket_url.socket_path);
+/// assert_eq!("/v1.41/containers/json", unix_socket_url.path);
+///
+/// let unix_socket_url = parse_unix_socket_url("unix:///run/app.sock").unwrap();
+/// assert_eq!(None, unix_socket_url.transport_scheme());
+/// assert_eq!("/run/app.sock", unix_socket_url.socket_path);
+/// assert_eq!("", unix_socket_url.path);
+/// ```
+pub fn parse_unix_socket_url(url: &str) -> Result<UnixSocketUrl, String> {
+    let url = url.split_once('#').map(|(url, _)| url).unwrap_or(url);
+
+    let boxed_scheme = url.split_once(':');
+    if boxed_scheme.is_none() {
+        return Err(["error: not a Unix socket URL: ", url].join(""));
+    }
+    let (scheme, remaining_url) = boxed_scheme.unwrap();
+    let lowercase_scheme = scheme.to_ascii_lowercase();
+    let is_plain_unix = lowercase_scheme == "unix";
+    if !is_valid_scheme(scheme) || (!is_plain_unix && !lowercase_scheme.ends_with("+unix")) {
+        return Err(["error: not a Unix socket URL: ", url].join(""));
+    }
+
+    let (remaining_url, boxed_query) = remaining_url.split_once('?')
+        .map(|(remaining_url, query)| (remaining_url, Some(query)))
+        .unwrap_or((remaining_url, None));
+
+    let mut authority = "";
+    let mut path = remaining_url;
+    if let Some(authority_and_path) = remaining_url.strip_prefix("//") {
+        let authority_end = authority_and_path.find('/').unwrap_or(authority_and_path.len());
+        authority = &authority_and_path[..authority_end];
+        path = &authority_and_path[authority_end..];
+    } else if !is_plain_unix {
+        return Err(["error: Unix socket URL must have socket path as authority: ", url].join(""));
+    }
+
+    if !is_valid_encoded(path, is_path_char) {
+        return Err(["error: Unix socket URL path is not valid: ", path].join(""));
+    }
+
+    let socket_path : String;
+    if authority.is_empty() && is_plain_unix {
+        socket_path = percent_decode_to_string(path);
+        path = "";
+    } else {
+        if !is_valid_encoded(authority, is_reg_name_char) {
+            return Err(["error: Unix socket path in authority must be percent-encoded: ", authority].join(""));
+        }
+        socket_path = percent_decode_to_string(authority);
+    }
+
+    if socket_path.is_empty() {
+        return Err(["error: Unix socket URL has no socket path: ", url].join(""));
+    }
+
+    Ok(UnixSocketUrl {
+        scheme: scheme.to_string(),
+        socket_path,
+        path: path.to_string(),
+        query: boxed_query.map(|query| parse_query_params(query, QueryEncoding::Rfc3986))
+    })
+}
+
+/// Builds Unix socket URL, socket path is percent-encoded into authority,
+/// plain `unix` URL without request path and query keeps socket path as URL path,
+/// `/` is inserted before request path that does not start with it
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_unix_socket_url, UnixSocketUrl};
+///
+/// let unix_socket_url = UnixSocketUrl::new("/var/run/docker.sock", "/v1.41/containers/json");
+/// assert_eq!("http+unix://%2Fvar%2Frun%2Fdocker.sock/v1.41/containers/json", build_unix_socket_url(&unix_socket_url));
+///
+/// let unix_socket_url = UnixSocketUrl { scheme: "unix".to_string(), ..UnixSocketUrl::new("/run/app.sock", "") };
+/// assert_eq!("unix:///run/app.sock", build_unix_socket_url(&unix_socket_url));
+/// ```
+pub fn build_unix_socket_url(unix_socket_url: &UnixSocketUrl) -> String {
+    let is_plain_unix = unix_socket_url.scheme.eq_ignore_ascii_case("unix");
+    let is_socket_in_path = is_plain_unix
+        && unix_socket_url.path.is_empty()
+        && unix_socket_url.query.is_none()
+        && unix_socket_url.socket_path.starts_with('/');
+
+    let mut url = [unix_socket_url.scheme.as_str(), "://"].join("");
+    if is_socket_in_path {
+        url = [url.as_str(), percent_encode(unix_socket_url.socket_path.as_str(), is_path_char).as_str()].join("");
+    } else {
+        let authority = percent_encode(unix_socket_url.socket_path.as_str(), is_reg_name_char);
+        url = [url.as_str(), authority.as_str()].join("");
+
+        let path = unix_socket_url.path.as_str();
+        if !path.is_empty() && !path.starts_with('/') {
+            url = [url.as_str(), "/"].join("");
+        }
+        url = [url.as_str(), path].join("");
+    }
+
+    if let Some(query) = &unix_socket_url.query {
+        let query = build_query_params(query, QueryEncoding::Rfc3986);
+        url = [url.as_str(), "?", query.as_str()].join("");
+    }
+    url
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_unix_socket_url, parse_unix_socket_url, UnixSocketUrl};
+
+    #[test]
+    fn parse_unix_socket_url_uppercase_scheme_with_query_and_fragment() {
+        let unix_socket_url = parse_unix_socket_url("HTTPS+UNIX://%2Ftmp%2Fa%20b.sock?x=1#top").unwrap();
+
+        assert_eq!("HTTPS", unix_socket_url.transport_scheme().unwrap());
+        assert_eq!("/tmp/a b.sock", unix_socket_url.socket_path);
+        assert_eq!("", unix_socket_url.path);
+        assert_eq!("/?x=1", unix_socket_url.request_target());
+    }
+
+    #[test]
+    fn parse_unix_socket_url_plain_unix_path() {
+        let unix_socket_url = parse_unix_socket_url("unix:/run/app.sock").unwrap();
+
+        assert_eq!("/run/app.sock", unix_socket_url.socket_path);
+    }
+
+    #[test]
+    fn parse_unix_socket_url_plain_unix_authority() {
+        let unix_socket_url = parse_unix_socket_url("unix://%2Frun%2Fapp.sock/health").unwrap();
+
+        assert_eq!("/run/app.sock", unix_socket_url.socket_path);
+        assert_eq!("/health", unix_socket_url.path);
+    }
+
+    #[test]
+    fn parse_unix_socket_url_abstract_socket() {
+        let unix_socket_url = parse_unix_socket_url("ws+unix://%40abstract/events").unwrap();
+
+        assert_eq!(UnixSocketUrl { scheme: "ws+unix".to_string(), ..UnixSocketUrl::new("@abstract", "/events") }, unix_socket_url);
+    }
+
+    #[test]
+    fn parse_unix_socket_url_other_scheme() {
+        assert_eq!("error: not a Unix socket URL: /var/run/docker.sock", parse_unix_socket_url("/var/run/docker.sock").err().unwrap());
+        assert_eq!("error: not a Unix socket URL: http://localhost/", parse_unix_socket_url("http://localhost/").err().unwrap());
+        assert_eq!("error: not a Unix socket URL: +unix://%2Fa/", parse_unix_socket_url("+unix://%2Fa/").err().unwrap());
+        assert_eq!("error: not a Unix socket URL: h_t+unix://%2Fa/", parse_unix_socket_url("h_t+unix://%2Fa/").err().unwrap());
+        assert_eq!("error: not a Unix socket URL: 1+unix://%2Fa/", parse_unix_socket_url("1+unix://%2Fa/").err().unwrap());
+    }
+
+    #[test]
+    fn parse_unix_socket_url_transport_without_authority() {
+        let boxed_unix_socket_url = parse_unix_socket_url("http+unix:/var/run/docker.sock");
+
+        assert!(boxed_unix_socket_url.is_err());
+        assert_eq!(
+            "error: Unix socket URL must have socket path as authority: http+unix:/var/run/docker.sock",
+            boxed_unix_socket_url.err().unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_unix_socket_url_without_socket_path() {
+        assert_eq!("error: Unix socket URL has no socket path: http+unix:///v1/info", parse_unix_socket_url("http+unix:///v1/info").err().unwrap());
+        assert_eq!("error: Unix socket URL has no socket path: unix:", parse_unix_socket_url("unix:").err().unwrap());
+    }
+
+    #[test]
+    fn parse_unix_socket_url_unencoded_socket_path() {
+        let boxed_unix_socket_url = parse_unix_socket_url("http+unix://a:b/");
+
+        assert!(boxed_unix_socket_url.is_err());
+        assert_eq!("error: Unix socket path in authority must be percent-encoded: a:b", boxed_unix_socket_url.err().unwrap());
+    }
+
+    #[test]
+    fn parse_unix_socket_url_invalid_path() {
+        let boxed_unix_socket_url = parse_unix_socket_url("http+unix://%2Fa/b c");
+
+        assert!(boxed_unix_socket_url.is_err());
+        assert_eq!("error: Unix socket URL path is not valid: /b c", boxed_unix_socket_url.err().unwrap());
+    }
+
+    #[test]
+    fn unix_socket_url_round_trip_with_query() {
+        let url = "http+unix://%2Fvar%2Frun%2Fdocker.sock/v1.41/containers/json?all=1&filters=%7B%7D";
+
+        assert_eq!(url, build_unix_socket_url(&parse_unix_socket_url(url).unwrap()));
+    }
+
+    #[test]
+    fn unix_socket_url_round_trip_socket_path_in_path() {
+        let url = "unix:///run/app.sock";
+
+        assert_eq!(url, build_unix_socket_url(&parse_unix_socket_url(url).unwrap()));
+    }
+
+    #[test]
+    fn unix_socket_url_round_trip_plain_unix_with_request_path() {
+        let url = "unix://%2Frun%2Fapp.sock/status";
+
+        assert_eq!(url, build_unix_socket_url(&parse_unix_socket_url(url).unwrap()));
+    }
+
+    #[test]
+    fn build_unix_socket_url_path_without_slash() {
+        let unix_socket_url = UnixSocketUrl::new("/run/app.sock", "containers/json");
+
+        let url = build_unix_socket_url(&unix_socket_url);
+        assert_eq!("http+unix://%2Frun%2Fapp.sock/containers/json", url);
+        assert_eq!("/containers/json", parse_unix_socket_url(url.as_str()).unwrap().path);
+        assert_eq!("/containers/json", unix_socket_url.request_target());
+    }
+
+    #[test]
+    fn unix_socket_url_round_trip_relative_socket_path() {
+        let url = "http+unix://relative.sock/";
+
+        assert_eq!(url, build_unix_socket_url(&parse_unix_socket_url(url).unwrap()));
+    }
+}
@@ -0,0 +1,309 @@
+use crate::percent_encoding::{is_fragment_char, is_path_char, is_valid_encoded};
+
+/// Uniform Resource Name, see [RFC 8141](https://www.rfc-editor.org/rfc/rfc8141)
+///
+/// - `nid`: namespace identifier as written
+/// - `nss`: namespace specific string, percent-encoded as written
+/// - `r_component`: resolution parameters after `?+`
+/// - `q_component`: query after `?=`
+/// - `f_component`: fragment after `#`
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Urn {
+    pub nid: String,
+    pub nss: String,
+    pub r_component: Option<String>,
+    pub q_component: Option<String>,
+    pub f_component: Option<String>
+}
+
+impl Urn {
+    /// Creates URN with given namespace identifier and namespace specific string
+    pub fn new(nid: &str, nss: &str) -> Urn {
+        Urn {
+            nid: nid.to_string(),
+            nss: nss.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `urn:<NID>:<NSS>` without r-, q- and f-components
+    pub fn assigned_name(&self) -> String {
+        ["urn:", self.nid.as_str(), ":", self.nss.as_str()].join("")
+    }
+
+    /// Returns assigned name normalized for [RFC 8141 section 3](https://www.rfc-editor.org/rfc/rfc8141#section-3)
+    /// lexical equivalence: `urn` and NID are lowercased, hex digits of percent-encoded octets are uppercased.
+    /// Equivalent URNs have the same key, so it can be used in maps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_urn;
+    ///
+    /// let urn = parse_urn("URN:ISBN:0-395-36341-1%2f2?=lang=en#p1").unwrap();
+    /// assert_eq!("urn:isbn:0-395-36341-1%2F2", urn.equivalence_key());
+    /// ```
+    pub fn equivalence_key(&self) -> String {
+        ["urn:", self.nid.to_ascii_lowercase().as_str(), ":", uppercase_percent_encoding(self.nss.as_str()).as_str()].join("")
+    }
+
+    /// Returns true if URNs are lexically equivalent, r-, q- and f-components are not compared
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url_build_parse::parse_urn;
+    ///
+    /// let urn = parse_urn("urn:example:a123%2cz456").unwrap();
+    /// assert!(urn.is_equivalent(&parse_urn("URN:EXAMPLE:a123%2Cz456?+abc").unwrap()));
+    /// assert!(!urn.is_equivalent(&parse_urn("urn:example:A123%2cz456").unwrap()));
+    /// assert!(!urn.is_equivalent(&parse_urn("urn:example:a123,z456").unwrap()));
+    /// ```
+    pub fn is_equivalent(&self, other: &Urn) -> bool {
+        self.equivalence_key() == other.equivalence_key()
+    }
+}
+
+/// Parses URN in form `urn:<NID>:<NSS>[?+<r-component>][?=<q-component>][#<f-component>]`
+///
+/// NID is 2 to 32 letters, digits or hyphens and does not start or end with hyphen,
+/// NSS and components must be ASCII with valid percent-encoding.
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::parse_urn;
+///
+/// let urn = parse_urn("urn:example:weather?+CCResolve:cc=uk?=op=map&lat=39.56#top").unwrap();
+/// assert_eq!("example", urn.nid);
+/// assert_eq!("weather", urn.nss);
+/// assert_eq!("CCResolve:cc=uk", urn.r_component.unwrap());
+/// assert_eq!("op=map&lat=39.56", urn.q_component.unwrap());
+/// assert_eq!("top", urn.f_component.unwrap());
+///
+/// assert_eq!("error: URN namespace identifier is not valid: -x", parse_urn("urn:-x:1").err().unwrap());
+/// ```
+pub fn parse_urn(urn: &str) -> Result<Urn, String> {
+    let boxed_scheme = urn.split_once(':');
+    if boxed_scheme.is_none() || !boxed_scheme.unwrap().0.eq_ignore_ascii_case("urn") {
+        return Err(["error: not a URN: ", urn].join(""));
+    }
+
+    let boxed_nid = boxed_scheme.unwrap().1.split_once(':');
+    if boxed_nid.is_none() {
+        return Err(["error: URN has no namespace specific string: ", urn].join(""));
+    }
+    let (nid, mut remaining_urn) = boxed_nid.unwrap();
+    if !is_valid_nid(nid) {
+        return Err(["error: URN namespace identifier is not valid: ", nid].join(""));
+    }
+
+    let mut f_component : Option<String> = None;
+    if let Some((without_fragment, fragment)) = remaining_urn.split_once('#') {
+        if !is_valid_component(fragment, is_fragment_char) {
+            return Err(["error: URN f-component is not valid: ", fragment].join(""));
+        }
+        f_component = Some(fragment.to_string());
+        remaining_urn = without_fragment;
+    }
+
+    let mut q_component : Option<String> = None;
+    if let Some((without_query, query)) = remaining_urn.split_once("?=") {
+        if query.is_empty() || query.starts_with('/') || query.starts_with('?') || !is_valid_component(query, is_fragment_char) {
+            return Err(["error: URN q-component is not valid: ", query].join(""));
+        }
+        q_component = Some(query.to_string());
+        remaining_urn = without_query;
+    }
+
+    let mut r_component : Option<String> = None;
+    if let Some((without_resolution, resolution)) = remaining_urn.split_once("?+") {
+        if resolution.is_empty() || resolution.starts_with('/') || resolution.starts_with('?') || !is_valid_component(resolution, is_fragment_char) {
+            return Err(["error: URN r-component is not valid: ", resolution].join(""));
+        }
+        r_component = Some(resolution.to_string());
+        remaining_urn = without_resolution;
+    }
+
+    let nss = remaining_urn;
+    if nss.is_empty() || nss.starts_with('/') || !is_valid_component(nss, is_path_char) {
+        return Err(["error: URN namespace specific string is not valid: ", nss].join(""));
+    }
+
+    Ok(Urn {
+        nid: nid.to_string(),
+        nss: nss.to_string(),
+        r_component,
+        q_component,
+        f_component
+    })
+}
+
+/// Builds URN string, components are written as is
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{build_urn, Urn};
+///
+/// let urn = Urn {
+///     q_component: Some("lang=en".to_string()),
+///     ..Urn::new("isbn", "0451450523")
+/// };
+/// assert_eq!("urn:isbn:0451450523?=lang=en", build_urn(&urn));
+/// ```
+pub fn build_urn(urn: &Urn) -> String {
+    let mut built_urn = urn.assigned_name();
+    if let Some(r_component) = &urn.r_component {
+        built_urn = [built_urn.as_str(), "?+", r_component].join("");
+    }
+    if let Some(q_component) = &urn.q_component {
+        built_urn = [built_urn.as_str(), "?=", q_component].join("");
+    }
+    if let Some(f_component) = &urn.f_component {
+        built_urn = [built_urn.as_str(), "#", f_component].join("");
+    }
+    built_urn
+}
+
+fn is_valid_nid(nid: &str) -> bool {
+    (2..=32).contains(&nid.len())
+        && !nid.starts_with('-')
+        && !nid.ends_with('-')
+        && nid.chars().all(|char| char.is_ascii_alphanumeric() || char == '-')
+}
+
+fn is_valid_component(component: &str, allowed: fn(u8) -> bool) -> bool {
+    component.is_ascii() && is_valid_encoded(component, allowed)
+}
+
+fn uppercase_percent_encoding(input: &str) -> String {
+    let mut normalized = String::with_capacity(input.len());
+    let mut uppercase_left = 0;
+    for char in input.chars() {
+        if uppercase_left > 0 {
+            normalized.push(char.to_ascii_uppercase());
+            uppercase_left -= 1;
+            continue;
+        }
+        if char == '%' {
+            uppercase_left = 2;
+        }
+        normalized.push(char);
+    }
+    normalized
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_urn, parse_urn, Urn};
+
+    #[test]
+    fn parse_urn_examples() {
+        let urn = parse_urn("urn:oasis:names:specification:docbook:dtd:xml:4.1.2").unwrap();
+        assert_eq!(Urn::new("oasis", "names:specification:docbook:dtd:xml:4.1.2"), urn);
+
+        let urn = parse_urn("urn:example:foo-bar-baz-qux?+CCResolve:cc=uk").unwrap();
+        assert_eq!("CCResolve:cc=uk", urn.r_component.unwrap());
+        assert!(urn.q_component.is_none());
+
+        let urn = parse_urn("urn:example:a/b?=x=1?y#frag?/").unwrap();
+        assert_eq!("a/b", urn.nss);
+        assert!(urn.r_component.is_none());
+        assert_eq!("x=1?y", urn.q_component.unwrap());
+        assert_eq!("frag?/", urn.f_component.unwrap());
+    }
+
+    #[test]
+    fn parse_urn_other_scheme() {
+        let boxed_urn = parse_urn("mailto:a@x");
+
+        assert!(boxed_urn.is_err());
+        assert_eq!("error: not a URN: mailto:a@x", boxed_urn.err().unwrap());
+    }
+
+    #[test]
+    fn parse_urn_without_nss() {
+        let boxed_urn = parse_urn("urn:isbn");
+
+        assert!(boxed_urn.is_err());
+        assert_eq!("error: URN has no namespace specific string: urn:isbn", boxed_urn.err().unwrap());
+    }
+
+    #[test]
+    fn parse_urn_invalid_nid() {
+        assert_eq!("error: URN namespace identifier is not valid: a", parse_urn("urn:a:1").err().unwrap());
+        assert_eq!("error: URN namespace identifier is not valid: x-", parse_urn("urn:x-:1").err().unwrap());
+        assert_eq!("error: URN namespace identifier is not valid: ex_ample", parse_urn("urn:ex_ample:1").err().unwrap());
+        assert_eq!(
+            "error: URN namespace identifier is not valid: abcdefghijklmnopqrstuvwxyz0123456",
+            parse_urn("urn:abcdefghijklmnopqrstuvwxyz0123456:1").err().unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_urn_invalid_nss() {
+        assert_eq!("error: URN namespace specific string is not valid: ", parse_urn("urn:example:").err().unwrap());
+        assert_eq!("error: URN namespace specific string is not valid: /a", parse_urn("urn:example:/a").err().unwrap());
+        assert_eq!("error: URN namespace specific string is not valid: a b", parse_urn("urn:example:a b").err().unwrap());
+        assert_eq!("error: URN namespace specific string is not valid: a%2", parse_urn("urn:example:a%2").err().unwrap());
+        assert_eq!("error: URN namespace specific string is not valid: é", parse_urn("urn:example:é").err().unwrap());
+        assert_eq!("error: URN namespace specific string is not valid: a?b", parse_urn("urn:example:a?b").err().unwrap());
+    }
+
+    #[test]
+    fn parse_urn_invalid_components() {
+        assert_eq!("error: URN r-component is not valid: ", parse_urn("urn:example:a?+").err().unwrap());
+        assert_eq!("error: URN q-component is not valid: /x", parse_urn("urn:example:a?=/x").err().unwrap());
+        assert_eq!("error: URN f-component is not valid: x y", parse_urn("urn:example:a#x y").err().unwrap());
+    }
+
+    #[test]
+    fn urn_lexical_equivalence() {
+        let equivalent = [
+            "urn:example:a123,z456",
+            "URN:example:a123,z456",
+            "urn:EXAMPLE:a123,z456",
+            "urn:example:a123,z456?+abc",
+            "urn:example:a123,z456?=xyz",
+            "urn:example:a123,z456#789",
+        ];
+        for urn in equivalent.iter() {
+            assert!(parse_urn(equivalent[0]).unwrap().is_equivalent(&parse_urn(urn).unwrap()), "{}", urn);
+        }
+
+        let different = [
+            "urn:example:a123,z456/foo",
+            "urn:example:a123,z456/bar",
+            "urn:example:a123%2Cz456",
+            "urn:example:A123,z456",
+        ];
+        for urn in different.iter() {
+            assert!(!parse_urn(equivalent[0]).unwrap().is_equivalent(&parse_urn(urn).unwrap()), "{}", urn);
+        }
+
+        assert!(parse_urn("urn:example:a%2cb%c3%a9").unwrap().is_equivalent(&parse_urn("urn:example:a%2Cb%C3%A9").unwrap()));
+    }
+
+    #[test]
+    fn urn_round_trip() {
+        let urn = "urn:uuid:6e8bc430-9c3a-11d9-9669-0800200c9a66";
+
+        assert_eq!(urn, build_urn(&parse_urn(urn).unwrap()));
+    }
+
+    #[test]
+    fn urn_round_trip_keeps_nss_encoding_and_components() {
+        let urn = "urn:example:a%2Fb?+r=1?=q=2#f";
+
+        assert_eq!(urn, build_urn(&parse_urn(urn).unwrap()));
+    }
+
+    #[test]
+    fn urn_round_trip_q_component_without_r_component() {
+        let urn = "urn:example:x?=q";
+
+        assert_eq!(urn, build_urn(&parse_urn(urn).unwrap()));
+    }
+}
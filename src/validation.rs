@@ -0,0 +1,247 @@
+use std::fmt;
+use crate::percent_encoding::{is_fragment_char, is_path_char, is_reg_name_char, is_unreserved, is_userinfo_char, is_valid_encoded};
+use crate::UrlComponents;
+
+/// Problem found while validating URL components against [RFC 3986 section 3](https://www.rfc-editor.org/rfc/rfc3986#section-3)
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum UrlValidationError {
+    EmptyScheme,
+    InvalidScheme(String),
+    MissingHost,
+    PasswordWithoutUsername,
+    InvalidUsername(String),
+    InvalidPassword(String),
+    InvalidHost(String),
+    InvalidPort(usize),
+    RelativePathWithAuthority(String),
+    PathStartsWithDoubleSlash(String),
+    InvalidPath(String),
+    InvalidFragment(String),
+}
+
+impl fmt::Display for UrlValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UrlValidationError::EmptyScheme =>
+                write!(f, "scheme is empty"),
+            UrlValidationError::InvalidScheme(scheme) =>
+                write!(f, "scheme contains not allowed characters: {}", scheme),
+            UrlValidationError::MissingHost =>
+                write!(f, "user info and port require host to be defined"),
+            UrlValidationError::PasswordWithoutUsername =>
+                write!(f, "password requires username to be defined"),
+            UrlValidationError::InvalidUsername(username) =>
+                write!(f, "username contains not allowed characters: {}", username),
+            UrlValidationError::InvalidPassword(password) =>
+                write!(f, "password contains not allowed characters: {}", password),
+            UrlValidationError::InvalidHost(host) =>
+                write!(f, "host contains not allowed characters: {}", host),
+            UrlValidationError::InvalidPort(port) =>
+                write!(f, "port is out of range: {}", port),
+            UrlValidationError::RelativePathWithAuthority(path) =>
+                write!(f, "path must be empty or start with '/' when authority is defined: {}", path),
+            UrlValidationError::PathStartsWithDoubleSlash(path) =>
+                write!(f, "path must not start with '//' when authority is not defined: {}", path),
+            UrlValidationError::InvalidPath(path) =>
+                write!(f, "path contains not allowed characters: {}", path),
+            UrlValidationError::InvalidFragment(fragment) =>
+                write!(f, "fragment contains not allowed characters: {}", fragment),
+        }
+    }
+}
+
+impl std::error::Error for UrlValidationError {}
+
+/// Checks constraints between URL components, returns every problem found
+///
+/// # Examples
+///
+/// ```
+/// use url_build_parse::{validate_url_components, UrlComponents, UrlValidationError};
+///
+/// let mut url_components = UrlComponents::new();
+/// url_components.path = "//path".to_string();
+///
+/// let errors = validate_url_components(&url_components).err().unwrap();
+/// assert_eq!(errors, vec![
+///     UrlValidationError::EmptyScheme,
+///     UrlValidationError::PathStartsWithDoubleSlash("//path".to_string())
+/// ]);
+/// ```
+pub fn validate_url_components(url_components: &UrlComponents) -> Result<(), Vec<UrlValidationError>> {
+    let mut errors : Vec<UrlValidationError> = vec![];
+
+    let scheme = url_components.scheme.as_str();
+    if scheme.is_empty() {
+        errors.push(UrlValidationError::EmptyScheme);
+    } else if !is_valid_scheme(scheme) {
+        errors.push(UrlValidationError::InvalidScheme(scheme.to_string()));
+    }
+
+    let path = url_components.path.as_str();
+    if let Some(authority) = &url_components.authority {
+        if let Some(user_info) = &authority.user_info {
+            if !is_valid_encoded(user_info.username.as_str(), is_username_char) {
+                errors.push(UrlValidationError::InvalidUsername(user_info.username.to_string()));
+            }
+            if let Some(password) = &user_info.password {
+                if !is_valid_encoded(password.as_str(), is_userinfo_char) {
+                    errors.push(UrlValidationError::InvalidPassword(password.to_string()));
+                }
+            }
+        }
+
+        if !is_valid_host(authority.host.as_str()) {
+            errors.push(UrlValidationError::InvalidHost(authority.host.to_string()));
+        }
+
+        if let Some(port) = authority.port.filter(|port| *port > u16::MAX as usize) {
+            errors.push(UrlValidationError::InvalidPort(port));
+        }
+
+        if !path.is_empty() && !path.starts_with('/') {
+            errors.push(UrlValidationError::RelativePathWithAuthority(path.to_string()));
+        }
+    } else if path.starts_with("//") {
+        errors.push(UrlValidationError::PathStartsWithDoubleSlash(path.to_string()));
+    }
+
+    if !is_valid_encoded(path, is_path_char) {
+        errors.push(UrlValidationError::InvalidPath(path.to_string()));
+    }
+
+    if let Some(fragment) = &url_components.fragment {
+        if !is_valid_encoded(fragment.as_str(), is_fragment_char) {
+            errors.push(UrlValidationError::InvalidFragment(fragment.to_string()));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+pub(crate) fn validate_url_components_to_string(url_components: &UrlComponents) -> Result<(), String> {
+    validate_url_components(url_components).map_err(|errors| {
+        let errors : Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        errors.join(", ")
+    })
+}
+
+pub(crate) fn is_valid_scheme(scheme: &str) -> bool {
+    let mut bytes = scheme.bytes();
+    let boxed_first = bytes.next();
+    if boxed_first.is_none() || !boxed_first.unwrap().is_ascii_alphabetic() {
+        return false;
+    }
+
+    bytes.all(|byte| byte.is_ascii_alphanumeric() || byte == b'+' || byte == b'-' || byte == b'.')
+}
+
+pub(crate) fn is_valid_host(host: &str) -> bool {
+    if host.starts_with('[') {
+        if !host.ends_with(']') || host.len() < 2 {
+            return false;
+        }
+        let ip_literal = &host[1..host.len() - 1];
+        return !ip_literal.is_empty() && ip_literal.bytes().all(is_ip_literal_char);
+    }
+
+    is_valid_encoded(host, is_reg_name_char)
+}
+
+pub(crate) fn is_username_char(byte: u8) -> bool {
+    is_userinfo_char(byte) && byte != b':'
+}
+
+fn is_ip_literal_char(byte: u8) -> bool {
+    is_unreserved(byte) || byte == b':' || byte == b'%'
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_url, parse_url, validate_url_components, UrlAuthority, UrlComponents, UrlUserInfo, UrlValidationError};
+
+    #[test]
+    fn validate_valid_components() {
+        let url_components = UrlComponents {
+            scheme: "https".to_string(),
+            authority: Some(UrlAuthority {
+                user_info: Some(UrlUserInfo { username: "usr".to_string(), password: Some("p:wd".to_string()) }),
+                host: "[2001:db8::7]".to_string(),
+                port: Some(443)
+            }),
+            path: "/a%20b/c".to_string(),
+            query: None,
+            fragment: Some("frag/?".to_string())
+        };
+
+        assert!(validate_url_components(&url_components).is_ok());
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        let url_components = UrlComponents {
+            scheme: "1http".to_string(),
+            authority: Some(UrlAuthority {
+                user_info: Some(UrlUserInfo { username: "u:sr".to_string(), password: None }),
+                host: "host/path@".to_string(),
+                port: Some(70000)
+            }),
+            path: "path".to_string(),
+            query: None,
+            fragment: Some("a#b".to_string())
+        };
+
+        let errors = validate_url_components(&url_components).err().unwrap();
+        assert_eq!(errors, vec![
+            UrlValidationError::InvalidScheme("1http".to_string()),
+            UrlValidationError::InvalidUsername("u:sr".to_string()),
+            UrlValidationError::InvalidHost("host/path@".to_string()),
+            UrlValidationError::InvalidPort(70000),
+            UrlValidationError::RelativePathWithAuthority("path".to_string()),
+            UrlValidationError::InvalidFragment("a#b".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn validate_raw_non_ascii() {
+        let mut url_components = UrlComponents::new();
+        url_components.scheme = "https".to_string();
+        url_components.authority = Some(UrlAuthority { user_info: None, host: "bücher.de".to_string(), port: None });
+        url_components.path = "/straße".to_string();
+        url_components.fragment = Some("ü".to_string());
+
+        let errors = validate_url_components(&url_components).err().unwrap();
+        assert_eq!(errors, vec![
+            UrlValidationError::InvalidHost("bücher.de".to_string()),
+            UrlValidationError::InvalidPath("/straße".to_string()),
+            UrlValidationError::InvalidFragment("ü".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn validate_empty_query_key() {
+        let url_components = parse_url("https://somehost/?=value").unwrap();
+
+        assert!(validate_url_components(&url_components).is_ok());
+        assert_eq!("https://somehost/?=value", build_url(url_components).unwrap());
+    }
+
+    #[test]
+    fn validate_path_without_authority() {
+        let mut url_components = UrlComponents::new();
+        url_components.scheme = "news".to_string();
+        url_components.path = "//comp.infosystems".to_string();
+
+        let errors = validate_url_components(&url_components).err().unwrap();
+        assert_eq!(errors, vec![UrlValidationError::PathStartsWithDoubleSlash("//comp.infosystems".to_string())]);
+
+        url_components.path = "comp.infosystems/a?b".to_string();
+        let errors = validate_url_components(&url_components).err().unwrap();
+        assert_eq!(errors, vec![UrlValidationError::InvalidPath("comp.infosystems/a?b".to_string())]);
+    }
+}